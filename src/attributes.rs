@@ -0,0 +1,437 @@
+use crate::types::{
+    Attribute, Declaration, Deprecated, Enum, EnumVariant, Field, Function, FunctionParameter,
+    GenericParam, LintLevel, NamedField, Struct, StructFields, TupleField, TyExpr, Union,
+};
+use proc_macro2::{Delimiter, Group, Punct, Spacing, TokenStream, TokenTree};
+
+// Adds queries for well-known attributes to a type with attributes.
+macro_rules! impl_attribute_queries {
+    ($ty:ty, |$self:ident| $attributes:expr) => {
+        impl $ty {
+            /// Returns true if there is a `#[non_exhaustive]` attribute.
+            pub fn is_non_exhaustive(&self) -> bool {
+                let $self = self;
+                $attributes
+                    .iter()
+                    .any(|attribute| attribute.has_path("non_exhaustive"))
+            }
+
+            /// Returns the contents of the `#[deprecated]` attribute, if any.
+            ///
+            /// Both `#[deprecated = "note"]` and `#[deprecated(since = "...", note = "...")]`
+            /// are supported.
+            pub fn deprecated(&self) -> Option<Deprecated> {
+                let $self = self;
+                let attribute = $attributes
+                    .iter()
+                    .find(|attribute| attribute.has_path("deprecated"))?;
+                Some(parse_deprecated(attribute))
+            }
+
+            /// Returns true if there is a `#[must_use]` attribute.
+            pub fn is_must_use(&self) -> bool {
+                let $self = self;
+                $attributes
+                    .iter()
+                    .any(|attribute| attribute.has_path("must_use"))
+            }
+
+            /// Returns the message of a `#[must_use = "message"]` attribute, if any.
+            pub fn must_use_message(&self) -> Option<String> {
+                let $self = self;
+                $attributes
+                    .iter()
+                    .filter(|attribute| attribute.has_path("must_use"))
+                    .find_map(|attribute| attribute_value(attribute))
+            }
+
+            /// Returns true if there is a `#[doc(hidden)]` attribute.
+            pub fn is_doc_hidden(&self) -> bool {
+                let $self = self;
+                $attributes.iter().any(|attribute| {
+                    attribute.has_path("doc")
+                        && attribute_list(attribute)
+                            .iter()
+                            .any(|item| matches!(item.as_slice(), [TokenTree::Ident(ident)] if ident == "hidden"))
+                })
+            }
+
+            /// Returns the level set for `lint` by an `allow`, `warn`, `deny` or
+            /// `forbid` attribute, eg `lint_level("dead_code")`.
+            ///
+            /// If several attributes mention the lint, the last one wins.
+            pub fn lint_level(&self, lint: &str) -> Option<LintLevel> {
+                let $self = self;
+                let lint: String = lint.chars().filter(|c| !c.is_whitespace()).collect();
+                let mut level = None;
+                for attribute in $attributes.iter() {
+                    let attribute_level = match lint_attribute_level(attribute) {
+                        Some(attribute_level) => attribute_level,
+                        None => continue,
+                    };
+                    let mentions_lint = attribute_list(attribute)
+                        .iter()
+                        .any(|item| tokens_to_path(item) == lint);
+                    if mentions_lint {
+                        level = Some(attribute_level);
+                    }
+                }
+                level
+            }
+
+            /// Returns the `allow`, `warn`, `deny` and `forbid` attributes.
+            ///
+            /// Derives can add these attributes to the code they generate.
+            pub fn lint_attributes(&self) -> Vec<&Attribute> {
+                let $self = self;
+                $attributes
+                    .iter()
+                    .filter(|attribute| lint_attribute_level(attribute).is_some())
+                    .collect()
+            }
+
+            /// Returns the paths listed in `#[derive(...)]` attributes, eg `Clone`
+            /// or `serde::Serialize`.
+            pub fn derives(&self) -> Vec<TyExpr> {
+                let $self = self;
+                $attributes
+                    .iter()
+                    .filter(|attribute| attribute.has_path("derive"))
+                    .flat_map(attribute_list)
+                    .filter(|item| !item.is_empty())
+                    .map(|tokens| TyExpr { tokens })
+                    .collect()
+            }
+
+            /// Returns true if `path` is listed in a `#[derive(...)]` attribute.
+            ///
+            /// The path must be written the same way, eg `"serde::Serialize"`
+            /// doesn't match `#[derive(Serialize)]`.
+            pub fn has_derive(&self, path: &str) -> bool {
+                let path: String = path.chars().filter(|c| !c.is_whitespace()).collect();
+                self.derives()
+                    .iter()
+                    .any(|derive| tokens_to_path(&derive.tokens) == path)
+            }
+        }
+    };
+}
+
+impl_attribute_queries!(Declaration, |this| this.attributes());
+impl_attribute_queries!(EnumVariant, |this| this.attributes);
+impl_attribute_queries!(NamedField, |this| this.attributes);
+impl_attribute_queries!(TupleField, |this| this.attributes);
+impl_attribute_queries!(Field<'_>, |this| this.attributes);
+
+impl EnumVariant {
+    /// Returns true if there is a `#[default]` attribute.
+    pub fn is_default(&self) -> bool {
+        self.attributes
+            .iter()
+            .any(|attribute| attribute.has_path("default"))
+    }
+}
+
+impl Enum {
+    /// Returns the variant with a `#[default]` attribute, if any.
+    pub fn default_variant(&self) -> Option<&EnumVariant> {
+        self.variants.items().find(|variant| variant.is_default())
+    }
+}
+
+// Adds `retain_attributes` to a type with an `attributes: Vec<Attribute>` field.
+macro_rules! impl_retain_attributes {
+    ($($ty:ty),*) => {
+        $(
+            impl $ty {
+                /// Keeps only the attributes for which `f` returns true.
+                pub fn retain_attributes(&mut self, f: impl FnMut(&Attribute) -> bool) {
+                    self.attributes.retain(f);
+                }
+            }
+        )*
+    };
+}
+
+impl_retain_attributes!(
+    Struct,
+    Enum,
+    Union,
+    Function,
+    EnumVariant,
+    NamedField,
+    TupleField,
+    FunctionParameter,
+    GenericParam
+);
+
+impl Declaration {
+    /// Keeps only the attributes for which `f` returns true.
+    ///
+    /// Only the attributes of the declaration itself are affected. See
+    /// [`Declaration::strip_attributes`] to remove attributes from fields,
+    /// variants and parameters too.
+    pub fn retain_attributes(&mut self, f: impl FnMut(&Attribute) -> bool) {
+        self.attributes_mut().retain(f);
+    }
+
+    /// Removes the attributes whose path is `path` from the declaration and all
+    /// its fields, variants, generic params and function params.
+    ///
+    /// Attribute macros can use this to remove their helper attributes before
+    /// emitting the declaration.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let mut declaration = parse_declaration(quote!(
+    ///     enum Hello {
+    ///         #[my_attr(skip)]
+    ///         A(#[my_attr] u8),
+    ///     }
+    /// ));
+    /// declaration.strip_attributes("my_attr");
+    /// assert_eq!(
+    ///     quote!(#declaration).to_string(),
+    ///     quote!(enum Hello { A(u8), }).to_string(),
+    /// );
+    /// ```
+    pub fn strip_attributes(&mut self, path: &str) {
+        let mut is_kept = |attribute: &Attribute| !attribute.has_path(path);
+
+        let generic_params = match self {
+            Declaration::Struct(struct_decl) => {
+                struct_decl.retain_attributes(&mut is_kept);
+                strip_field_attributes(&mut struct_decl.fields, &mut is_kept);
+                &mut struct_decl.generic_params
+            }
+            Declaration::Enum(enum_decl) => {
+                enum_decl.retain_attributes(&mut is_kept);
+                for (variant, _punct) in &mut enum_decl.variants.inner {
+                    variant.retain_attributes(&mut is_kept);
+                    strip_field_attributes(&mut variant.contents, &mut is_kept);
+                }
+                &mut enum_decl.generic_params
+            }
+            Declaration::Union(union_decl) => {
+                union_decl.retain_attributes(&mut is_kept);
+                for (field, _punct) in &mut union_decl.fields.fields.inner {
+                    field.retain_attributes(&mut is_kept);
+                }
+                &mut union_decl.generic_params
+            }
+            Declaration::Function(function_decl) => {
+                function_decl.retain_attributes(&mut is_kept);
+                for (param, _punct) in &mut function_decl.params.inner {
+                    param.retain_attributes(&mut is_kept);
+                }
+                &mut function_decl.generic_params
+            }
+        };
+
+        if let Some(generic_params) = generic_params {
+            for (param, _punct) in &mut generic_params.params.inner {
+                param.retain_attributes(&mut is_kept);
+            }
+        }
+    }
+}
+
+impl Attribute {
+    /// Creates an attribute from a path and the tokens following it.
+    ///
+    /// ```
+    /// # use venial::Attribute;
+    /// # use quote::quote;
+    /// let allow = Attribute::new("allow", quote!((dead_code)));
+    /// let doc = Attribute::new("doc", quote!(= "Hello world"));
+    /// let skip = Attribute::new("rustfmt::skip", quote!());
+    /// assert_eq!(quote!(#allow).to_string(), quote!(#[allow(dead_code)]).to_string());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` isn't made of identifiers separated by `::`, like
+    /// `rustfmt::skip`.
+    pub fn new(path: &str, args: TokenStream) -> Self {
+        let path_tokens: Vec<TokenTree> = match path.parse::<TokenStream>() {
+            Ok(tokens) => tokens.into_iter().collect(),
+            Err(_) => Vec::new(),
+        };
+        if !is_simple_path(&path_tokens) {
+            panic!("invalid attribute path `{}`", path);
+        }
+        let child_tokens: Vec<TokenTree> = path_tokens.into_iter().chain(args).collect();
+
+        Attribute {
+            _hashbang: Punct::new('#', Spacing::Alone),
+            _braces: Group::new(Delimiter::Bracket, child_tokens.iter().cloned().collect()),
+            child_tokens,
+        }
+    }
+}
+
+fn strip_field_attributes(fields: &mut StructFields, is_kept: &mut impl FnMut(&Attribute) -> bool) {
+    match fields {
+        StructFields::Unit => (),
+        StructFields::Tuple(tuple_fields) => {
+            for (field, _punct) in &mut tuple_fields.fields.inner {
+                field.retain_attributes(&mut *is_kept);
+            }
+        }
+        StructFields::Named(named_fields) => {
+            for (field, _punct) in &mut named_fields.fields.inner {
+                field.retain_attributes(&mut *is_kept);
+            }
+        }
+    }
+}
+
+// Returns true for `a`, `a::b` or `::a::b`.
+fn is_simple_path(tokens: &[TokenTree]) -> bool {
+    let is_colon =
+        |token: &TokenTree| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ':');
+    let mut tokens = tokens;
+    if let [colon1, colon2, rest @ ..] = tokens {
+        if is_colon(colon1) && is_colon(colon2) {
+            tokens = rest;
+        }
+    }
+    loop {
+        match tokens {
+            [TokenTree::Ident(_)] => return true,
+            [TokenTree::Ident(_), colon1, colon2, rest @ ..]
+                if is_colon(colon1) && is_colon(colon2) =>
+            {
+                tokens = rest;
+            }
+            _ => return false,
+        }
+    }
+}
+
+// Returns the `(...)` group of `#[attr(...)]`.
+pub(crate) fn attribute_args(attribute: &Attribute) -> Option<&Group> {
+    match attribute.child_tokens.as_slice() {
+        [_name, TokenTree::Group(group)] if group.delimiter() == Delimiter::Parenthesis => {
+            Some(group)
+        }
+        _ => None,
+    }
+}
+
+// Returns the comma-separated items of `#[attr(a, b = c, d(e))]`.
+fn attribute_list(attribute: &Attribute) -> Vec<Vec<TokenTree>> {
+    match attribute_args(attribute) {
+        Some(args) => split_on_commas(args.stream()),
+        None => Vec::new(),
+    }
+}
+
+// Returns `value` for `#[attr = "value"]`.
+fn attribute_value(attribute: &Attribute) -> Option<String> {
+    match attribute.child_tokens.as_slice() {
+        [_name, TokenTree::Punct(eq), TokenTree::Literal(literal)] if eq.as_char() == '=' => {
+            parse_string_literal(&literal.to_string())
+        }
+        _ => None,
+    }
+}
+
+fn parse_deprecated(attribute: &Attribute) -> Deprecated {
+    let mut deprecated = Deprecated {
+        since: None,
+        note: attribute_value(attribute),
+    };
+    for item in attribute_list(attribute) {
+        match item.as_slice() {
+            [TokenTree::Ident(key), TokenTree::Punct(eq), TokenTree::Literal(value)]
+                if eq.as_char() == '=' =>
+            {
+                let value = parse_string_literal(&value.to_string());
+                if key == "since" {
+                    deprecated.since = value;
+                } else if key == "note" {
+                    deprecated.note = value;
+                }
+            }
+            _ => (),
+        }
+    }
+    deprecated
+}
+
+fn lint_attribute_level(attribute: &Attribute) -> Option<LintLevel> {
+    if attribute.has_path("allow") {
+        Some(LintLevel::Allow)
+    } else if attribute.has_path("warn") {
+        Some(LintLevel::Warn)
+    } else if attribute.has_path("deny") {
+        Some(LintLevel::Deny)
+    } else if attribute.has_path("forbid") {
+        Some(LintLevel::Forbid)
+    } else {
+        None
+    }
+}
+
+fn tokens_to_path(tokens: &[TokenTree]) -> String {
+    let tokens: TokenStream = tokens.iter().cloned().collect();
+    tokens
+        .to_string()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect()
+}
+
+pub(crate) fn split_on_commas(tokens: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut chunks = vec![Vec::new()];
+    for token in tokens {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => chunks.push(Vec::new()),
+            token => chunks.last_mut().unwrap().push(token),
+        }
+    }
+    // Ignore trailing comma
+    if chunks.len() > 1 && chunks.last().unwrap().is_empty() {
+        chunks.pop();
+    }
+    chunks
+}
+
+// Returns the value of a string literal, eg `"a\"b"` becomes `a"b`.
+pub(crate) fn parse_string_literal(literal: &str) -> Option<String> {
+    if let Some(raw) = literal.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let raw = &raw[hashes..raw.len().checked_sub(hashes)?];
+        let value = raw.strip_prefix('"')?.strip_suffix('"')?;
+        return Some(value.to_string());
+    }
+
+    let escaped = literal.strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::new();
+    let mut chars = escaped.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => value.push('\n'),
+            'r' => value.push('\r'),
+            't' => value.push('\t'),
+            '0' => value.push('\0'),
+            '\\' => value.push('\\'),
+            '\'' => value.push('\''),
+            '"' => value.push('"'),
+            // Line continuation
+            '\n' => {
+                let rest = chars.as_str().trim_start();
+                chars = rest.chars();
+            }
+            // Unicode and byte escapes aren't supported
+            _ => return None,
+        }
+    }
+    Some(value)
+}
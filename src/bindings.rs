@@ -0,0 +1,199 @@
+use crate::types::{Binding, BindingMode, Enum, EnumVariant, Struct, StructFields};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+
+impl Struct {
+    /// Returns one binding per field, with the names used by [`Struct::binding_pattern`].
+    pub fn bindings(&self) -> Vec<Binding<'_>> {
+        bindings(&self.fields)
+    }
+
+    /// Returns a pattern destructuring `Self` into its bindings.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, BindingMode};
+    /// # use quote::quote;
+    /// let struct_type = parse_declaration(quote!(
+    ///     struct Hello { a: u8, b: String }
+    /// ));
+    /// let struct_type = struct_type.as_struct().unwrap();
+    /// assert_eq!(
+    ///     struct_type.binding_pattern(BindingMode::Ref).to_string(),
+    ///     quote!(Self { a: ref __binding_0, b: ref __binding_1 }).to_string(),
+    /// );
+    /// ```
+    pub fn binding_pattern(&self, mode: BindingMode) -> TokenStream {
+        binding_pattern(quote!(Self), &self.fields, mode)
+    }
+
+    /// Returns a `match` expression on `self` with a single arm, whose body is
+    /// the concatenation of `f` called on each binding.
+    ///
+    /// With [`BindingMode::Ref`] and [`BindingMode::RefMut`], the expression
+    /// matches on `*self`, so it can be used in methods taking `&self` and
+    /// `&mut self` respectively. With [`BindingMode::Move`], it matches on
+    /// `self`.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, BindingMode};
+    /// # use quote::quote;
+    /// let struct_type = parse_declaration(quote!(
+    ///     struct Hello { a: u8 }
+    /// ));
+    /// let struct_type = struct_type.as_struct().unwrap();
+    /// let match_expr = struct_type.each_field(BindingMode::RefMut, |binding| {
+    ///     let ident = &binding.ident;
+    ///     quote!(*#ident += 1;)
+    /// });
+    /// assert_eq!(
+    ///     match_expr.to_string(),
+    ///     quote!(match *self {
+    ///         Self { a: ref mut __binding_0 } => { *__binding_0 += 1; }
+    ///     }).to_string(),
+    /// );
+    /// ```
+    pub fn each_field(
+        &self,
+        mode: BindingMode,
+        mut f: impl FnMut(&Binding) -> TokenStream,
+    ) -> TokenStream {
+        let scrutinee = scrutinee(mode, false);
+        let pattern = self.binding_pattern(mode);
+        let body = self.bindings().iter().map(&mut f).collect::<TokenStream>();
+        quote!(match #scrutinee {
+            #pattern => { #body }
+        })
+    }
+}
+
+impl EnumVariant {
+    /// Returns one binding per field, with the names used by [`EnumVariant::binding_pattern`].
+    pub fn bindings(&self) -> Vec<Binding<'_>> {
+        bindings(&self.contents)
+    }
+
+    /// Returns a pattern destructuring `Self::Variant` into its bindings,
+    /// eg `Self::Variant(__binding_0, __binding_1)`.
+    pub fn binding_pattern(&self, mode: BindingMode) -> TokenStream {
+        let name = &self.name;
+        binding_pattern(quote!(Self::#name), &self.contents, mode)
+    }
+}
+
+impl Enum {
+    /// Returns a `match` expression on `self` with one arm per variant.
+    ///
+    /// See [`Struct::each_field`] for how `self` is matched. The body of each
+    /// arm is the concatenation of `f` called on each binding
+    /// of the variant. If the enum is `#[non_exhaustive]`, a `_ => {}` arm is
+    /// added at the end.
+    pub fn each_field(
+        &self,
+        mode: BindingMode,
+        mut f: impl FnMut(&Binding) -> TokenStream,
+    ) -> TokenStream {
+        self.each_variant(mode, |_variant, bindings| {
+            let body = bindings.iter().map(&mut f).collect::<TokenStream>();
+            quote!({ #body })
+        })
+    }
+
+    /// Returns a `match` expression on `self` with one arm per variant.
+    ///
+    /// See [`Struct::each_field`] for how `self` is matched. The body of each
+    /// arm is the result of `f` called on the variant and its
+    /// bindings. If the enum is `#[non_exhaustive]`, a `_ => {}` arm is added
+    /// at the end; `f` must then return expressions of type `()`.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, BindingMode};
+    /// # use quote::quote;
+    /// let enum_type = parse_declaration(quote!(
+    ///     enum Hello { A(u8), B { b: u8, c: u8 } }
+    /// ));
+    /// let enum_type = enum_type.as_enum().unwrap();
+    /// let match_expr = enum_type.each_variant(BindingMode::Move, |_variant, bindings| {
+    ///     let idents = bindings.iter().map(|binding| &binding.ident);
+    ///     quote!(0 #(+ #idents)*)
+    /// });
+    /// assert_eq!(
+    ///     match_expr.to_string(),
+    ///     quote!(match self {
+    ///         Self::A(__binding_0) => 0 + __binding_0,
+    ///         Self::B { b: __binding_0, c: __binding_1 } => 0 + __binding_0 + __binding_1,
+    ///     }).to_string(),
+    /// );
+    /// ```
+    pub fn each_variant(
+        &self,
+        mode: BindingMode,
+        mut f: impl FnMut(&EnumVariant, &[Binding]) -> TokenStream,
+    ) -> TokenStream {
+        let arms = self.variants.items().map(|variant| {
+            let pattern = variant.binding_pattern(mode);
+            let body = f(variant, &variant.bindings());
+            quote!(#pattern => #body,)
+        });
+        let arms = arms.collect::<TokenStream>();
+
+        let is_non_exhaustive = self
+            .attributes
+            .iter()
+            .any(|attribute| attribute.has_path("non_exhaustive"));
+        let wildcard_arm = if is_non_exhaustive {
+            quote!(
+                #[allow(unreachable_patterns)]
+                _ => {}
+            )
+        } else {
+            TokenStream::new()
+        };
+
+        let scrutinee = scrutinee(mode, self.variants.is_empty());
+
+        quote!(match #scrutinee {
+            #arms
+            #wildcard_arm
+        })
+    }
+}
+
+// Patterns with explicit `ref` and `ref mut` bindings must match on a place,
+// as the 2024 edition rejects them under default binding modes, and `ref mut`
+// can't borrow through a shared reference.
+fn scrutinee(mode: BindingMode, is_empty_enum: bool) -> TokenStream {
+    match mode {
+        // A reference to an empty enum isn't considered uninhabited by match.
+        BindingMode::Move if !is_empty_enum => quote!(self),
+        _ => quote!(*self),
+    }
+}
+
+fn bindings(fields: &StructFields) -> Vec<Binding<'_>> {
+    fields
+        .iter()
+        .map(|field| Binding {
+            field,
+            ident: Ident::new(&format!("__binding_{}", field.index), Span::mixed_site()),
+        })
+        .collect()
+}
+
+fn binding_pattern(path: TokenStream, fields: &StructFields, mode: BindingMode) -> TokenStream {
+    let mode = match mode {
+        BindingMode::Move => quote!(),
+        BindingMode::Ref => quote!(ref),
+        BindingMode::RefMut => quote!(ref mut),
+    };
+    let bindings = bindings(fields);
+    let idents = bindings.iter().map(|binding| &binding.ident);
+
+    match fields {
+        StructFields::Unit => path,
+        StructFields::Tuple(_) => quote!(#path(#(#mode #idents),*)),
+        StructFields::Named(_) => {
+            let members = bindings.iter().map(|binding| binding.field.member());
+            quote!(#path { #(#members: #mode #idents),* })
+        }
+    }
+}
@@ -0,0 +1,542 @@
+#![allow(missing_docs)]
+
+use crate::types::{
+    Attribute, Enum, EnumDiscriminant, EnumVariant, Function, FunctionParameter,
+    FunctionQualifiers, GenericParam, NamedField, NamedStructFields, Struct, StructFields,
+    TupleField, TupleStructFields, TyExpr, Union, VisMarker, WhereClause, WhereClauseItem,
+};
+use crate::Punctuated;
+use proc_macro2::{Delimiter, Group, Ident, Punct, Spacing, Span, TokenStream, TokenTree};
+use quote::ToTokens;
+
+// Tokens added by the `with_*` methods get the span of the item's name, so
+// that a span chosen with `new_spanned` is used throughout.
+
+impl Struct {
+    /// Creates a unit struct, eg `struct Foo;`.
+    ///
+    /// Fields, attributes, etc can then be added with the `with_*` methods:
+    ///
+    /// ```
+    /// # use venial::{NamedField, Struct, VisMarker};
+    /// # use quote::quote;
+    /// let struct_type = Struct::new("FooBuilder")
+    ///     .with_vis(VisMarker::public())
+    ///     .with_named_field(NamedField::new("x", quote!(Option<u32>)));
+    /// assert_eq!(
+    ///     quote!(#struct_type).to_string(),
+    ///     quote!(pub struct FooBuilder { x: Option<u32> }).to_string(),
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid identifier.
+    pub fn new(name: &str) -> Self {
+        Self::new_spanned(name, Span::call_site())
+    }
+
+    /// Same as [`Struct::new`], with every token using `span`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid identifier.
+    pub fn new_spanned(name: &str, span: Span) -> Self {
+        Struct {
+            attributes: Vec::new(),
+            vis_marker: None,
+            _struct: Ident::new("struct", span),
+            name: Ident::new(name, span),
+            generic_params: None,
+            where_clause: None,
+            fields: StructFields::Unit,
+            _semicolon: Some(new_punct(';', span)),
+        }
+    }
+
+    pub fn with_attribute(mut self, attribute: Attribute) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+
+    pub fn with_vis(mut self, vis_marker: VisMarker) -> Self {
+        self.vis_marker = Some(vis_marker);
+        self
+    }
+
+    /// Replaces the fields of the struct, adding or removing the final `;` as needed.
+    pub fn with_fields(mut self, fields: StructFields) -> Self {
+        self._semicolon = match fields {
+            StructFields::Named(_) => None,
+            _ => Some(new_punct(';', self.name.span())),
+        };
+        self.fields = fields;
+        self
+    }
+
+    /// Adds a field, turning a unit struct into a struct with named fields.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the struct is a tuple struct.
+    pub fn with_named_field(mut self, field: NamedField) -> Self {
+        let span = self.name.span();
+        if self.fields.is_unit() {
+            self = self.with_fields(StructFields::Named(NamedStructFields::new(span)));
+        }
+        push_named_field(
+            &mut self.fields,
+            field,
+            "cannot add a named field to a tuple struct",
+        );
+        self
+    }
+
+    /// Adds a field, turning a unit struct into a tuple struct.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the struct has named fields.
+    pub fn with_tuple_field(mut self, field: TupleField) -> Self {
+        let span = self.name.span();
+        if self.fields.is_unit() {
+            self = self.with_fields(StructFields::Tuple(TupleStructFields::new(span)));
+        }
+        push_tuple_field(
+            &mut self.fields,
+            field,
+            "cannot add a tuple field to a struct with named fields",
+        );
+        self
+    }
+}
+
+impl Enum {
+    /// Creates an enum without variants, eg `enum Foo {}`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid identifier.
+    pub fn new(name: &str) -> Self {
+        Self::new_spanned(name, Span::call_site())
+    }
+
+    /// Same as [`Enum::new`], with every token using `span`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid identifier.
+    pub fn new_spanned(name: &str, span: Span) -> Self {
+        Enum {
+            attributes: Vec::new(),
+            vis_marker: None,
+            _enum: Ident::new("enum", span),
+            name: Ident::new(name, span),
+            generic_params: None,
+            where_clause: None,
+            tk_braces: new_group(Delimiter::Brace, span),
+            variants: Punctuated::new(),
+        }
+    }
+
+    pub fn with_attribute(mut self, attribute: Attribute) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+
+    pub fn with_vis(mut self, vis_marker: VisMarker) -> Self {
+        self.vis_marker = Some(vis_marker);
+        self
+    }
+
+    pub fn with_variant(mut self, variant: EnumVariant) -> Self {
+        self.variants.push(variant, None);
+        self
+    }
+}
+
+impl EnumVariant {
+    /// Creates a unit variant, eg `Foo`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid identifier.
+    pub fn new(name: &str) -> Self {
+        Self::new_spanned(name, Span::call_site())
+    }
+
+    /// Same as [`EnumVariant::new`], with every token using `span`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid identifier.
+    pub fn new_spanned(name: &str, span: Span) -> Self {
+        EnumVariant {
+            attributes: Vec::new(),
+            vis_marker: None,
+            name: Ident::new(name, span),
+            contents: StructFields::Unit,
+            discriminant: None,
+        }
+    }
+
+    pub fn with_attribute(mut self, attribute: Attribute) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+
+    /// Adds a field, turning a unit variant into a variant with named fields.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the variant is a tuple variant.
+    pub fn with_named_field(mut self, field: NamedField) -> Self {
+        if self.contents.is_unit() {
+            self.contents = StructFields::Named(NamedStructFields::new(self.name.span()));
+        }
+        push_named_field(
+            &mut self.contents,
+            field,
+            "cannot add a named field to a tuple variant",
+        );
+        self
+    }
+
+    /// Adds a field, turning a unit variant into a tuple variant.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the variant has named fields.
+    pub fn with_tuple_field(mut self, field: TupleField) -> Self {
+        if self.contents.is_unit() {
+            self.contents = StructFields::Tuple(TupleStructFields::new(self.name.span()));
+        }
+        push_tuple_field(
+            &mut self.contents,
+            field,
+            "cannot add a tuple field to a variant with named fields",
+        );
+        self
+    }
+
+    /// Sets the discriminant of the variant, eg `with_discriminant(quote!(1 << 4))`.
+    pub fn with_discriminant(mut self, value: impl ToTokens) -> Self {
+        let mut tokens: Vec<TokenTree> = vec![new_punct('=', self.name.span()).into()];
+        tokens.extend(value.into_token_stream());
+        self.discriminant = Some(EnumDiscriminant { tokens });
+        self
+    }
+}
+
+impl Union {
+    /// Creates a union without fields, eg `union Foo {}`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid identifier.
+    pub fn new(name: &str) -> Self {
+        Self::new_spanned(name, Span::call_site())
+    }
+
+    /// Same as [`Union::new`], with every token using `span`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid identifier.
+    pub fn new_spanned(name: &str, span: Span) -> Self {
+        Union {
+            attributes: Vec::new(),
+            vis_marker: None,
+            _union: Ident::new("union", span),
+            name: Ident::new(name, span),
+            generic_params: None,
+            where_clause: None,
+            tk_braces: new_group(Delimiter::Brace, span),
+            fields: NamedStructFields::new(span),
+        }
+    }
+
+    pub fn with_attribute(mut self, attribute: Attribute) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+
+    pub fn with_vis(mut self, vis_marker: VisMarker) -> Self {
+        self.vis_marker = Some(vis_marker);
+        self
+    }
+
+    pub fn with_field(mut self, field: NamedField) -> Self {
+        self.fields.fields.push(field, None);
+        self
+    }
+}
+
+impl Function {
+    /// Creates a function without parameters or body, eg `fn foo();`.
+    ///
+    /// ```
+    /// # use venial::{Function, FunctionParameter};
+    /// # use quote::quote;
+    /// let function = Function::new("double")
+    ///     .with_fn_param(FunctionParameter::new("x", quote!(u32)))
+    ///     .with_return_ty(quote!(u32))
+    ///     .with_body(quote!(x * 2));
+    /// assert_eq!(
+    ///     quote!(#function).to_string(),
+    ///     quote!(fn double(x: u32) -> u32 { x * 2 }).to_string(),
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid identifier.
+    pub fn new(name: &str) -> Self {
+        Self::new_spanned(name, Span::call_site())
+    }
+
+    /// Same as [`Function::new`], with every token using `span`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid identifier.
+    pub fn new_spanned(name: &str, span: Span) -> Self {
+        Function {
+            attributes: Vec::new(),
+            vis_marker: None,
+            qualifiers: FunctionQualifiers::default(),
+            name: Ident::new(name, span),
+            generic_params: None,
+            params: Punctuated::new(),
+            where_clause: None,
+            return_ty: None,
+            body: None,
+        }
+    }
+
+    pub fn with_attribute(mut self, attribute: Attribute) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+
+    pub fn with_vis(mut self, vis_marker: VisMarker) -> Self {
+        self.vis_marker = Some(vis_marker);
+        self
+    }
+
+    /// Adds a generic parameter. See [`GenericParams::with_param`](crate::GenericParams::with_param).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the function already has a parameter with the same name.
+    pub fn with_param(mut self, param: GenericParam) -> Self {
+        let params = self.generic_params.take().unwrap_or_default();
+        let params = params.with_param(param);
+        self.generic_params = Some(params);
+        self
+    }
+
+    pub fn with_where_item(mut self, item: WhereClauseItem) -> Self {
+        if let Some(where_clause) = self.where_clause {
+            self.where_clause = Some(where_clause.with_item(item));
+        } else {
+            self.where_clause = Some(WhereClause::from_item(item));
+        }
+        self
+    }
+
+    pub fn with_fn_param(mut self, param: FunctionParameter) -> Self {
+        self.params.push(param, None);
+        self
+    }
+
+    pub fn with_return_ty(mut self, ty: impl ToTokens) -> Self {
+        self.return_ty = Some(new_ty(ty));
+        self
+    }
+
+    /// Sets the body of the function, without the surrounding braces.
+    pub fn with_body(mut self, body: TokenStream) -> Self {
+        let mut group = Group::new(Delimiter::Brace, body);
+        group.set_span(self.name.span());
+        self.body = Some(group);
+        self
+    }
+}
+
+impl FunctionParameter {
+    /// Creates a parameter, eg `FunctionParameter::new("x", quote!(u32))`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid identifier.
+    pub fn new(name: &str, ty: impl ToTokens) -> Self {
+        Self::new_spanned(name, ty, Span::call_site())
+    }
+
+    /// Same as [`FunctionParameter::new`], with the name and colon using `span`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid identifier.
+    pub fn new_spanned(name: &str, ty: impl ToTokens, span: Span) -> Self {
+        FunctionParameter {
+            attributes: Vec::new(),
+            name: Ident::new(name, span),
+            ty: new_ty(ty),
+        }
+    }
+
+    pub fn with_attribute(mut self, attribute: Attribute) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+}
+
+impl NamedField {
+    /// Creates a field, eg `NamedField::new("x", quote!(u32))`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid identifier.
+    pub fn new(name: &str, ty: impl ToTokens) -> Self {
+        Self::new_spanned(name, ty, Span::call_site())
+    }
+
+    /// Same as [`NamedField::new`], with the name and colon using `span`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid identifier.
+    pub fn new_spanned(name: &str, ty: impl ToTokens, span: Span) -> Self {
+        NamedField {
+            attributes: Vec::new(),
+            vis_marker: None,
+            name: Ident::new(name, span),
+            _colon: new_punct(':', span),
+            ty: new_ty(ty),
+        }
+    }
+
+    pub fn with_attribute(mut self, attribute: Attribute) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+
+    pub fn with_vis(mut self, vis_marker: VisMarker) -> Self {
+        self.vis_marker = Some(vis_marker);
+        self
+    }
+}
+
+impl TupleField {
+    /// Creates a field, eg `TupleField::new(quote!(u32))`.
+    pub fn new(ty: impl ToTokens) -> Self {
+        TupleField {
+            attributes: Vec::new(),
+            vis_marker: None,
+            ty: new_ty(ty),
+        }
+    }
+
+    /// Same as [`TupleField::new`], with every token of `ty` using `span`.
+    ///
+    /// A tuple field has no tokens of its own, so this is the same as calling
+    /// [`TupleField::set_span`] on the new field.
+    pub fn new_spanned(ty: impl ToTokens, span: Span) -> Self {
+        let mut field = Self::new(ty);
+        field.set_span(span);
+        field
+    }
+
+    pub fn with_attribute(mut self, attribute: Attribute) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+
+    pub fn with_vis(mut self, vis_marker: VisMarker) -> Self {
+        self.vis_marker = Some(vis_marker);
+        self
+    }
+}
+
+impl VisMarker {
+    /// Returns the `pub` visibility.
+    pub fn public() -> Self {
+        Self::public_spanned(Span::call_site())
+    }
+
+    /// Same as [`VisMarker::public`], with the `pub` keyword using `span`.
+    pub fn public_spanned(span: Span) -> Self {
+        VisMarker {
+            _token1: Ident::new("pub", span).into(),
+            _token2: None,
+        }
+    }
+
+    /// Returns a restricted visibility, eg `pub(crate)` for `restricted(quote!(crate))`.
+    pub fn restricted(scope: TokenStream) -> Self {
+        Self::restricted_spanned(scope, Span::call_site())
+    }
+
+    /// Same as [`VisMarker::restricted`], with the `pub` keyword and the
+    /// parentheses using `span`.
+    pub fn restricted_spanned(scope: TokenStream, span: Span) -> Self {
+        let mut group = Group::new(Delimiter::Parenthesis, scope);
+        group.set_span(span);
+        VisMarker {
+            _token1: Ident::new("pub", span).into(),
+            _token2: Some(group.into()),
+        }
+    }
+}
+
+impl TupleStructFields {
+    fn new(span: Span) -> Self {
+        TupleStructFields {
+            fields: Punctuated::new(),
+            tk_parens: new_group(Delimiter::Parenthesis, span),
+        }
+    }
+}
+
+impl NamedStructFields {
+    fn new(span: Span) -> Self {
+        NamedStructFields {
+            fields: Punctuated::new(),
+            tk_braces: new_group(Delimiter::Brace, span),
+        }
+    }
+}
+
+fn push_named_field(fields: &mut StructFields, field: NamedField, message: &str) {
+    match fields {
+        StructFields::Named(named_fields) => named_fields.fields.push(field, None),
+        _ => panic!("{}", message),
+    }
+}
+
+fn push_tuple_field(fields: &mut StructFields, field: TupleField, message: &str) {
+    match fields {
+        StructFields::Tuple(tuple_fields) => tuple_fields.fields.push(field, None),
+        _ => panic!("{}", message),
+    }
+}
+
+fn new_ty(ty: impl ToTokens) -> TyExpr {
+    TyExpr {
+        tokens: ty.into_token_stream().into_iter().collect(),
+    }
+}
+
+fn new_punct(ch: char, span: Span) -> Punct {
+    let mut punct = Punct::new(ch, Spacing::Alone);
+    punct.set_span(span);
+    punct
+}
+
+fn new_group(delimiter: Delimiter, span: Span) -> Group {
+    let mut group = Group::new(delimiter, TokenStream::new());
+    group.set_span(span);
+    group
+}
@@ -0,0 +1,171 @@
+use crate::types::{EnumVariant, Field, NamedField, RenameRule};
+use crate::IdentExt;
+use proc_macro2::Ident;
+
+impl RenameRule {
+    /// Every rule, in declaration order.
+    pub const ALL: [RenameRule; 8] = [
+        RenameRule::LowerCase,
+        RenameRule::UpperCase,
+        RenameRule::PascalCase,
+        RenameRule::CamelCase,
+        RenameRule::SnakeCase,
+        RenameRule::ScreamingSnakeCase,
+        RenameRule::KebabCase,
+        RenameRule::ScreamingKebabCase,
+    ];
+
+    /// Returns the rule with the given name, eg `"snake_case"`, or None if the
+    /// name isn't recognized.
+    pub fn from_name(name: &str) -> Option<Self> {
+        RenameRule::ALL
+            .iter()
+            .copied()
+            .find(|rule| rule.as_str() == name)
+    }
+
+    /// Returns the name of the rule, eg `"snake_case"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RenameRule::LowerCase => "lowercase",
+            RenameRule::UpperCase => "UPPERCASE",
+            RenameRule::PascalCase => "PascalCase",
+            RenameRule::CamelCase => "camelCase",
+            RenameRule::SnakeCase => "snake_case",
+            RenameRule::ScreamingSnakeCase => "SCREAMING_SNAKE_CASE",
+            RenameRule::KebabCase => "kebab-case",
+            RenameRule::ScreamingKebabCase => "SCREAMING-KEBAB-CASE",
+        }
+    }
+
+    /// Converts `name` to this case.
+    ///
+    /// Words are separated by `_`, `-`, and case changes. A run of uppercase
+    /// letters is an acronym, whose last letter starts a new word if it's
+    /// followed by a lowercase letter. Digits belong to the word before them.
+    /// Leading and trailing underscores are kept as-is, so that eg `__private`
+    /// and `private` don't get the same name.
+    ///
+    /// This differs from serde, which assumes that field names are in
+    /// snake_case and variant names in PascalCase, and splits variant names
+    /// before every uppercase letter: serde renames `HTTPServer` to
+    /// `h_t_t_p_server` in snake_case, whereas this returns `http_server`.
+    ///
+    /// ```
+    /// # use venial::RenameRule;
+    /// assert_eq!(RenameRule::SnakeCase.apply("HTTPServer2Go"), "http_server2_go");
+    /// assert_eq!(RenameRule::CamelCase.apply("user_id"), "userId");
+    /// assert_eq!(RenameRule::PascalCase.apply("__private"), "__Private");
+    /// assert_eq!(RenameRule::ScreamingKebabCase.apply("ÉtéIndien"), "ÉTÉ-INDIEN");
+    /// ```
+    pub fn apply(&self, name: &str) -> String {
+        let body = name.trim_start_matches('_');
+        let prefix = &name[..name.len() - body.len()];
+        let body = body.trim_end_matches('_');
+        let suffix = &name[prefix.len() + body.len()..];
+        format!("{}{}{}", prefix, self.apply_to_words(body), suffix)
+    }
+
+    fn apply_to_words(&self, name: &str) -> String {
+        let words = split_words(name);
+        match self {
+            RenameRule::LowerCase => join_words(&words, "", |_, word| word.to_lowercase()),
+            RenameRule::UpperCase => join_words(&words, "", |_, word| word.to_uppercase()),
+            RenameRule::PascalCase => join_words(&words, "", |_, word| capitalize(word)),
+            RenameRule::CamelCase => join_words(&words, "", |i, word| {
+                if i == 0 {
+                    word.to_lowercase()
+                } else {
+                    capitalize(word)
+                }
+            }),
+            RenameRule::SnakeCase => join_words(&words, "_", |_, word| word.to_lowercase()),
+            RenameRule::ScreamingSnakeCase => {
+                join_words(&words, "_", |_, word| word.to_uppercase())
+            }
+            RenameRule::KebabCase => join_words(&words, "-", |_, word| word.to_lowercase()),
+            RenameRule::ScreamingKebabCase => {
+                join_words(&words, "-", |_, word| word.to_uppercase())
+            }
+        }
+    }
+
+    /// Converts the name of `ident` to this case, without the `r#` prefix of
+    /// raw identifiers.
+    pub fn apply_to_ident(&self, ident: &Ident) -> String {
+        self.apply(&ident.unraw().to_string())
+    }
+}
+
+impl EnumVariant {
+    /// Returns the variant's name converted with `rule`.
+    pub fn renamed(&self, rule: RenameRule) -> String {
+        rule.apply_to_ident(&self.name)
+    }
+}
+
+impl NamedField {
+    /// Returns the field's name converted with `rule`.
+    pub fn renamed(&self, rule: RenameRule) -> String {
+        rule.apply_to_ident(&self.name)
+    }
+}
+
+impl<'a> Field<'a> {
+    /// Returns the field's name converted with `rule`, or None for tuple fields.
+    pub fn renamed(&self, rule: RenameRule) -> Option<String> {
+        self.name.map(|name| rule.apply_to_ident(name))
+    }
+}
+
+fn split_words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' || c == '-' {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+
+        if c.is_uppercase() && !word.is_empty() {
+            let prev = chars[i - 1];
+            let next_is_lowercase = matches!(chars.get(i + 1), Some(next) if next.is_lowercase());
+            if prev.is_lowercase()
+                || prev.is_numeric()
+                || (prev.is_uppercase() && next_is_lowercase)
+            {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.push(c);
+    }
+
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn join_words(words: &[String], separator: &str, f: impl Fn(usize, &str) -> String) -> String {
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| f(i, word))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
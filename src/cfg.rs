@@ -0,0 +1,325 @@
+use crate::attributes::{attribute_args, parse_string_literal, split_on_commas};
+use crate::error::Error;
+use crate::spans::join_spans;
+use crate::types::{
+    Attribute, CfgOptions, CfgPredicate, Declaration, EnumVariant, Field, GenericParams,
+    StructFields,
+};
+use crate::{Punctuated, Spanned};
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
+
+impl CfgOptions {
+    /// Returns an empty configuration, where every predicate but `all()` and
+    /// `not(...)` is false.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a name option, eg `unix` or `test`.
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.names.push(name.to_string());
+        self
+    }
+
+    /// Adds a key-value option, eg `feature = "std"` with `with_key_value("feature", "std")`.
+    pub fn with_key_value(mut self, key: &str, value: &str) -> Self {
+        self.key_values.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Returns true if the attribute isn't a `#[cfg(...)]` attribute, or if its
+    /// predicate is true.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the attribute is a malformed `#[cfg(...)]` attribute.
+    pub fn is_enabled(&self, attribute: &Attribute) -> Result<bool, Error> {
+        match attribute.cfg_predicate() {
+            Some(predicate) => Ok(predicate?.eval(self)),
+            None => Ok(true),
+        }
+    }
+
+    fn is_all_enabled(&self, attributes: &[Attribute]) -> Result<bool, Error> {
+        for attribute in attributes {
+            if !self.is_enabled(attribute)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn expand_cfg_attr(
+        &self,
+        attribute: Attribute,
+        output: &mut Vec<Attribute>,
+    ) -> Result<(), Error> {
+        if !attribute.has_path("cfg_attr") {
+            output.push(attribute);
+            return Ok(());
+        }
+
+        let args = attribute_args(&attribute).ok_or_else(|| {
+            Error::new(
+                attribute.span(),
+                "expected `#[cfg_attr(predicate, attributes)]`",
+            )
+        })?;
+        let mut args = split_on_commas(args.stream()).into_iter();
+        let predicate = args.next().unwrap_or_default();
+        let predicate = CfgPredicate::parse(predicate.into_iter().collect())?;
+        if !predicate.eval(self) {
+            return Ok(());
+        }
+
+        for child_tokens in args {
+            let mut braces = Group::new(Delimiter::Bracket, child_tokens.iter().cloned().collect());
+            braces.set_span(attribute._braces.span());
+            let new_attribute = Attribute {
+                _hashbang: attribute._hashbang.clone(),
+                _braces: braces,
+                child_tokens,
+            };
+            self.expand_cfg_attr(new_attribute, output)?;
+        }
+        Ok(())
+    }
+
+    fn apply_to_fields(&self, fields: &mut StructFields) -> Result<(), Error> {
+        match fields {
+            StructFields::Unit => Ok(()),
+            StructFields::Tuple(tuple_fields) => {
+                retain_enabled(&mut tuple_fields.fields, |field| {
+                    self.apply_item(&mut field.attributes)
+                })
+            }
+            StructFields::Named(named_fields) => {
+                retain_enabled(&mut named_fields.fields, |field| {
+                    self.apply_item(&mut field.attributes)
+                })
+            }
+        }
+    }
+
+    fn apply_to_generic_params(&self, params: &mut Option<GenericParams>) -> Result<(), Error> {
+        if let Some(generic_params) = params {
+            retain_enabled(&mut generic_params.params, |param| {
+                self.apply_item(&mut param.attributes)
+            })?;
+            if generic_params.params.is_empty() {
+                *params = None;
+            }
+        }
+        Ok(())
+    }
+
+    // Returns false if the item is disabled, and otherwise expands its attributes.
+    fn apply_item(&self, attributes: &mut Vec<Attribute>) -> Result<bool, Error> {
+        let mut expanded_attributes = Vec::new();
+        for attribute in attributes.drain(..) {
+            self.expand_cfg_attr(attribute, &mut expanded_attributes)?;
+        }
+        *attributes = expanded_attributes;
+
+        if !self.is_all_enabled(attributes)? {
+            return Ok(false);
+        }
+        attributes.retain(|attribute| !attribute.is_cfg());
+        Ok(true)
+    }
+}
+
+impl Declaration {
+    /// Evaluates the `#[cfg(...)]` and `#[cfg_attr(...)]` attributes of the
+    /// declaration and its fields, variants and parameters against `options`.
+    ///
+    /// `cfg_attr` attributes whose predicate is true are replaced by the
+    /// attributes they contain, and the others are removed. Fields, variants
+    /// and parameters with a false `cfg` predicate are removed, and the
+    /// remaining `cfg` attributes are removed.
+    ///
+    /// Returns false if the declaration itself is disabled.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, CfgOptions};
+    /// # use quote::quote;
+    /// let mut declaration = parse_declaration(quote!(
+    ///     #[cfg_attr(feature = "serde", derive(Serialize))]
+    ///     struct Hello {
+    ///         #[cfg(unix)]
+    ///         a: u8,
+    ///         #[cfg(not(unix))]
+    ///         b: u8,
+    ///     }
+    /// ));
+    /// let options = CfgOptions::new().with_name("unix").with_key_value("feature", "serde");
+    /// declaration.apply_cfg(&options).unwrap();
+    /// assert_eq!(
+    ///     quote!(#declaration).to_string(),
+    ///     quote!(#[derive(Serialize)] struct Hello { a: u8, }).to_string(),
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a `cfg` or `cfg_attr` attribute is malformed.
+    pub fn apply_cfg(&mut self, options: &CfgOptions) -> Result<bool, Error> {
+        let (attributes, generic_params) = match self {
+            Declaration::Struct(struct_decl) => {
+                options.apply_to_fields(&mut struct_decl.fields)?;
+                (&mut struct_decl.attributes, &mut struct_decl.generic_params)
+            }
+            Declaration::Enum(enum_decl) => {
+                retain_enabled(&mut enum_decl.variants, |variant| {
+                    if !options.apply_item(&mut variant.attributes)? {
+                        return Ok(false);
+                    }
+                    options.apply_to_fields(&mut variant.contents)?;
+                    Ok(true)
+                })?;
+                (&mut enum_decl.attributes, &mut enum_decl.generic_params)
+            }
+            Declaration::Union(union_decl) => {
+                retain_enabled(&mut union_decl.fields.fields, |field| {
+                    options.apply_item(&mut field.attributes)
+                })?;
+                (&mut union_decl.attributes, &mut union_decl.generic_params)
+            }
+            Declaration::Function(function_decl) => {
+                retain_enabled(&mut function_decl.params, |param| {
+                    options.apply_item(&mut param.attributes)
+                })?;
+                (
+                    &mut function_decl.attributes,
+                    &mut function_decl.generic_params,
+                )
+            }
+        };
+
+        options.apply_to_generic_params(generic_params)?;
+        options.apply_item(attributes)
+    }
+}
+
+impl Attribute {
+    /// Returns true for `#[cfg(...)]` attributes.
+    pub fn is_cfg(&self) -> bool {
+        self.has_path("cfg")
+    }
+
+    /// Returns the predicate of a `#[cfg(...)]` attribute, or None for other attributes.
+    pub fn cfg_predicate(&self) -> Option<Result<CfgPredicate, Error>> {
+        if !self.is_cfg() {
+            return None;
+        }
+        let predicate = match attribute_args(self) {
+            Some(args) => CfgPredicate::parse(args.stream()),
+            None => Err(Error::new(self.span(), "expected `#[cfg(predicate)]`")),
+        };
+        Some(predicate)
+    }
+}
+
+impl EnumVariant {
+    /// Returns the variant's `#[cfg(...)]` attributes.
+    ///
+    /// Derives can add these attributes to the code they generate for the variant,
+    /// eg match arms, so that it is disabled along with the variant.
+    pub fn cfg_attributes(&self) -> Vec<&Attribute> {
+        self.attributes
+            .iter()
+            .filter(|attribute| attribute.is_cfg())
+            .collect()
+    }
+
+    /// Returns true if the variant has `#[cfg(...)]` attributes.
+    pub fn is_cfg_gated(&self) -> bool {
+        self.attributes.iter().any(Attribute::is_cfg)
+    }
+}
+
+impl<'a> Field<'a> {
+    /// Returns the field's `#[cfg(...)]` attributes.
+    ///
+    /// See [`EnumVariant::cfg_attributes`].
+    pub fn cfg_attributes(&self) -> Vec<&'a Attribute> {
+        self.attributes
+            .iter()
+            .filter(|attribute| attribute.is_cfg())
+            .collect()
+    }
+
+    /// Returns true if the field has `#[cfg(...)]` attributes.
+    pub fn is_cfg_gated(&self) -> bool {
+        self.attributes.iter().any(Attribute::is_cfg)
+    }
+}
+
+impl CfgPredicate {
+    /// Parses the inside of a `#[cfg(...)]` attribute, eg `all(unix, feature = "std")`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tokens aren't a valid predicate.
+    pub fn parse(tokens: TokenStream) -> Result<Self, Error> {
+        let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+        let span = join_spans(tokens.iter().cloned());
+
+        match tokens.as_slice() {
+            [TokenTree::Ident(name)] => Ok(CfgPredicate::Name(name.to_string())),
+            [TokenTree::Ident(key), TokenTree::Punct(eq), TokenTree::Literal(value)]
+                if eq.as_char() == '=' =>
+            {
+                let value = parse_string_literal(&value.to_string())
+                    .ok_or_else(|| Error::new(span, "expected a string literal"))?;
+                Ok(CfgPredicate::KeyValue(key.to_string(), value))
+            }
+            [TokenTree::Ident(op), TokenTree::Group(group)]
+                if group.delimiter() == Delimiter::Parenthesis =>
+            {
+                let mut predicates = Vec::new();
+                for predicate in split_on_commas(group.stream()) {
+                    predicates.push(CfgPredicate::parse(predicate.into_iter().collect())?);
+                }
+                match op.to_string().as_str() {
+                    "all" => Ok(CfgPredicate::All(predicates)),
+                    "any" => Ok(CfgPredicate::Any(predicates)),
+                    "not" if predicates.len() == 1 => {
+                        Ok(CfgPredicate::Not(Box::new(predicates.remove(0))))
+                    }
+                    "not" => Err(Error::new(span, "`not` expects exactly one predicate")),
+                    _ => Err(Error::new(span, "expected `all`, `any` or `not`")),
+                }
+            }
+            _ => Err(Error::new(span, "expected a cfg predicate")),
+        }
+    }
+
+    /// Returns true if the predicate holds for `options`.
+    pub fn eval(&self, options: &CfgOptions) -> bool {
+        match self {
+            CfgPredicate::Name(name) => options.names.contains(name),
+            CfgPredicate::KeyValue(key, value) => options
+                .key_values
+                .iter()
+                .any(|(option_key, option_value)| option_key == key && option_value == value),
+            CfgPredicate::All(predicates) => predicates.iter().all(|pred| pred.eval(options)),
+            CfgPredicate::Any(predicates) => predicates.iter().any(|pred| pred.eval(options)),
+            CfgPredicate::Not(predicate) => !predicate.eval(options),
+        }
+    }
+}
+
+fn retain_enabled<T>(
+    items: &mut Punctuated<T>,
+    mut is_enabled: impl FnMut(&mut T) -> Result<bool, Error>,
+) -> Result<(), Error> {
+    let mut new_inner = Vec::new();
+    for (mut item, punct) in items.inner.drain(..) {
+        if is_enabled(&mut item)? {
+            new_inner.push((item, punct));
+        }
+    }
+    items.inner = new_inner;
+    Ok(())
+}
@@ -0,0 +1,378 @@
+use crate::error::Error;
+use crate::spans::join_spans;
+use crate::types::{DiscriminantValue, Enum, ReprInt};
+use proc_macro2::{Delimiter, Ident, Literal, Spacing, TokenTree};
+use quote::quote;
+
+impl Enum {
+    /// Computes the discriminant value of each variant, in declaration order.
+    ///
+    /// Variants without an explicit discriminant get the previous variant's
+    /// value plus one, or zero for the first variant. Explicit discriminants are
+    /// evaluated if they only use integer literals, arithmetic and bitwise
+    /// operators, `as` casts to integer types, and earlier variants of the
+    /// enum (eg `B = A | 0x10`). Other discriminants are returned as
+    /// [`DiscriminantValue::Expr`], and so are the implicit values following
+    /// them, eg `(some_const()) + 1`.
+    ///
+    /// The values are checked against the integer type given by `#[repr(...)]`,
+    /// or `isize` by default, which is assumed to be 64 bits wide.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, DiscriminantValue};
+    /// # use quote::quote;
+    /// let enum_type = parse_declaration(quote!(
+    ///     enum Hello { A = -1, B, C = 0x10, D }
+    /// ));
+    /// let values = enum_type.as_enum().unwrap().discriminant_values().unwrap();
+    /// assert!(matches!(values[1], DiscriminantValue::Int(0)));
+    /// assert!(matches!(values[3], DiscriminantValue::Int(17)));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a value doesn't fit in the enum's integer type, if
+    /// two variants have the same value, or if [`Enum::repr`] returns an error.
+    pub fn discriminant_values(&self) -> Result<Vec<DiscriminantValue>, Error> {
+        let repr_int = self.repr()?.int.unwrap_or(ReprInt::Isize);
+        let repr_name = repr_int.as_str();
+        let (min, max) = int_range(repr_int);
+
+        let mut values = Vec::new();
+        let mut known_values: Vec<(&Ident, i128)> = Vec::new();
+        // The last explicit discriminant we couldn't evaluate, and the number of
+        // variants since then.
+        let mut last_expr: Option<(&[TokenTree], u128)> = None;
+
+        for variant in self.variants.items() {
+            let value = if let Some(discriminant) = &variant.discriminant {
+                // Skip the `=` token
+                let expr = &discriminant.tokens[1..];
+                let span = join_spans(expr.iter().cloned());
+
+                let evaluator = Evaluator {
+                    enum_name: &self.name,
+                    repr_int,
+                    known_values: &known_values,
+                };
+                match evaluator.eval(expr) {
+                    Some(value) if value < min || value > max => {
+                        return Err(Error::new(
+                            span,
+                            format!(
+                                "discriminant value `{}` doesn't fit in `{}`",
+                                value, repr_name
+                            ),
+                        ));
+                    }
+                    Some(value) => {
+                        last_expr = None;
+                        DiscriminantValue::Int(value)
+                    }
+                    None => {
+                        last_expr = Some((expr, 0));
+                        DiscriminantValue::Expr(expr.to_vec())
+                    }
+                }
+            } else if let Some((expr, offset)) = &mut last_expr {
+                *offset += 1;
+                let offset = Literal::u128_unsuffixed(*offset);
+                let expr = quote!((#(#expr)*) + #offset);
+                DiscriminantValue::Expr(expr.into_iter().collect())
+            } else {
+                let value = match known_values.last() {
+                    Some((_name, previous_value)) => previous_value + 1,
+                    None => 0,
+                };
+                if value > max {
+                    return Err(Error::new(
+                        variant.name.span(),
+                        format!(
+                            "enum discriminant overflowed: `{}` would be `{}`, which doesn't fit in `{}`",
+                            variant.name, value, repr_name
+                        ),
+                    ));
+                }
+                DiscriminantValue::Int(value)
+            };
+
+            if let DiscriminantValue::Int(value) = value {
+                let duplicate = known_values.iter().find(|(_name, known)| *known == value);
+                if let Some((first_name, _value)) = duplicate {
+                    // Point at the explicit discriminant if there is one.
+                    let span = match &variant.discriminant {
+                        Some(discriminant) => join_spans(discriminant.tokens[1..].iter().cloned()),
+                        None => variant.name.span(),
+                    };
+                    return Err(Error::new(
+                        span,
+                        format!(
+                            "discriminant value `{}` assigned more than once, first to `{}`",
+                            value, first_name
+                        ),
+                    ));
+                }
+                known_values.push((&variant.name, value));
+            }
+            values.push(value);
+        }
+
+        Ok(values)
+    }
+}
+
+// Evaluates simple constant expressions.
+struct Evaluator<'a> {
+    enum_name: &'a Ident,
+    repr_int: ReprInt,
+    known_values: &'a [(&'a Ident, i128)],
+}
+
+#[derive(Clone, Copy)]
+enum BinaryOp {
+    Or,
+    Xor,
+    And,
+    Shl,
+    Shr,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl Evaluator<'_> {
+    fn eval(&self, tokens: &[TokenTree]) -> Option<i128> {
+        let mut pos = 0;
+        let value = self.eval_binary(tokens, &mut pos, 0)?;
+        if pos == tokens.len() {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    fn eval_binary(
+        &self,
+        tokens: &[TokenTree],
+        pos: &mut usize,
+        min_precedence: u8,
+    ) -> Option<i128> {
+        let mut lhs = self.eval_cast(tokens, pos)?;
+
+        while let Some((op, op_len)) = peek_binary_op(&tokens[*pos..]) {
+            let precedence = op.precedence();
+            if precedence < min_precedence {
+                break;
+            }
+            *pos += op_len;
+            let rhs = self.eval_binary(tokens, pos, precedence + 1)?;
+            lhs = op.apply(lhs, rhs)?;
+        }
+
+        Some(lhs)
+    }
+
+    fn eval_cast(&self, tokens: &[TokenTree], pos: &mut usize) -> Option<i128> {
+        let mut value = self.eval_unary(tokens, pos)?;
+
+        while let Some(TokenTree::Ident(ident)) = tokens.get(*pos) {
+            if ident != "as" {
+                return None;
+            }
+            let ty = match tokens.get(*pos + 1) {
+                Some(TokenTree::Ident(ty)) => ReprInt::from_ident(ty)?,
+                _ => return None,
+            };
+            *pos += 2;
+            value = cast(value, ty)?;
+        }
+
+        Some(value)
+    }
+
+    fn eval_unary(&self, tokens: &[TokenTree], pos: &mut usize) -> Option<i128> {
+        match tokens.get(*pos)? {
+            TokenTree::Punct(punct) if punct.as_char() == '-' => {
+                *pos += 1;
+                self.eval_unary(tokens, pos)?.checked_neg()
+            }
+            TokenTree::Punct(punct) if punct.as_char() == '!' => {
+                *pos += 1;
+                // Bitwise negation depends on the width of the type
+                cast(!self.eval_unary(tokens, pos)?, self.repr_int)
+            }
+            TokenTree::Literal(literal) => {
+                *pos += 1;
+                parse_int_literal(literal)
+            }
+            TokenTree::Group(group)
+                if matches!(group.delimiter(), Delimiter::Parenthesis | Delimiter::None) =>
+            {
+                *pos += 1;
+                let group_tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+                self.eval(&group_tokens)
+            }
+            TokenTree::Ident(_) => {
+                let path = consume_path(tokens, pos);
+                self.eval_path(&path)
+            }
+            _ => None,
+        }
+    }
+
+    // Looks up `A`, `Self::A` or `MyEnum::A` in earlier variants.
+    fn eval_path(&self, path: &[&Ident]) -> Option<i128> {
+        let name = match path {
+            [name] => name,
+            [prefix, name] if *prefix == "Self" || *prefix == self.enum_name => name,
+            _ => return None,
+        };
+        self.known_values
+            .iter()
+            .find(|(known_name, _value)| known_name == name)
+            .map(|(_name, value)| *value)
+    }
+}
+
+impl BinaryOp {
+    fn precedence(self) -> u8 {
+        match self {
+            BinaryOp::Or => 1,
+            BinaryOp::Xor => 2,
+            BinaryOp::And => 3,
+            BinaryOp::Shl | BinaryOp::Shr => 4,
+            BinaryOp::Add | BinaryOp::Sub => 5,
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => 6,
+        }
+    }
+
+    fn apply(self, lhs: i128, rhs: i128) -> Option<i128> {
+        match self {
+            BinaryOp::Or => Some(lhs | rhs),
+            BinaryOp::Xor => Some(lhs ^ rhs),
+            BinaryOp::And => Some(lhs & rhs),
+            BinaryOp::Shl => lhs.checked_shl(rhs.try_into().ok()?),
+            BinaryOp::Shr => lhs.checked_shr(rhs.try_into().ok()?),
+            BinaryOp::Add => lhs.checked_add(rhs),
+            BinaryOp::Sub => lhs.checked_sub(rhs),
+            BinaryOp::Mul => lhs.checked_mul(rhs),
+            BinaryOp::Div => lhs.checked_div(rhs),
+            BinaryOp::Rem => lhs.checked_rem(rhs),
+        }
+    }
+}
+
+// Returns the operator at the start of `tokens`, and how many tokens it takes.
+fn peek_binary_op(tokens: &[TokenTree]) -> Option<(BinaryOp, usize)> {
+    let punct = match tokens.first()? {
+        TokenTree::Punct(punct) => punct,
+        _ => return None,
+    };
+    let next_char = match tokens.get(1) {
+        Some(TokenTree::Punct(next)) if punct.spacing() == Spacing::Joint => Some(next.as_char()),
+        _ => None,
+    };
+
+    let op = match (punct.as_char(), next_char) {
+        ('<', Some('<')) => return Some((BinaryOp::Shl, 2)),
+        ('>', Some('>')) => return Some((BinaryOp::Shr, 2)),
+        // Comparisons, `&&`, `||`, compound assignments, etc
+        (_, Some('=' | '&' | '|' | '<' | '>')) => return None,
+        ('|', _) => BinaryOp::Or,
+        ('^', _) => BinaryOp::Xor,
+        ('&', _) => BinaryOp::And,
+        ('+', _) => BinaryOp::Add,
+        ('-', _) => BinaryOp::Sub,
+        ('*', _) => BinaryOp::Mul,
+        ('/', _) => BinaryOp::Div,
+        ('%', _) => BinaryOp::Rem,
+        _ => return None,
+    };
+    Some((op, 1))
+}
+
+fn consume_path<'a>(tokens: &'a [TokenTree], pos: &mut usize) -> Vec<&'a Ident> {
+    let mut path = Vec::new();
+    while let Some(TokenTree::Ident(ident)) = tokens.get(*pos) {
+        path.push(ident);
+        *pos += 1;
+
+        match (tokens.get(*pos), tokens.get(*pos + 1)) {
+            (Some(TokenTree::Punct(colon1)), Some(TokenTree::Punct(colon2)))
+                if colon1.as_char() == ':' && colon2.as_char() == ':' =>
+            {
+                *pos += 2;
+            }
+            _ => break,
+        }
+    }
+    path
+}
+
+fn parse_int_literal(literal: &Literal) -> Option<i128> {
+    let literal = literal.to_string().replace('_', "");
+
+    let mut digits = literal.as_str();
+    for suffix in INT_TYPES {
+        if let Some(stripped) = digits.strip_suffix(suffix) {
+            digits = stripped;
+            break;
+        }
+    }
+
+    let (digits, radix) = if let Some(digits) = digits.strip_prefix("0x") {
+        (digits, 16)
+    } else if let Some(digits) = digits.strip_prefix("0o") {
+        (digits, 8)
+    } else if let Some(digits) = digits.strip_prefix("0b") {
+        (digits, 2)
+    } else {
+        (digits, 10)
+    };
+
+    // Excludes eg `+1`, which `from_str_radix` accepts
+    if !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    i128::from_str_radix(digits, radix).ok()
+}
+
+const INT_TYPES: [&str; 12] = [
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+];
+
+// Note: `u128` values above `i128::MAX` aren't supported.
+fn int_range(repr_int: ReprInt) -> (i128, i128) {
+    match repr_int {
+        ReprInt::I8 => (i8::MIN.into(), i8::MAX.into()),
+        ReprInt::I16 => (i16::MIN.into(), i16::MAX.into()),
+        ReprInt::I32 => (i32::MIN.into(), i32::MAX.into()),
+        ReprInt::I64 | ReprInt::Isize => (i64::MIN.into(), i64::MAX.into()),
+        ReprInt::I128 => (i128::MIN, i128::MAX),
+        ReprInt::U8 => (0, u8::MAX.into()),
+        ReprInt::U16 => (0, u16::MAX.into()),
+        ReprInt::U32 => (0, u32::MAX.into()),
+        ReprInt::U64 | ReprInt::Usize => (0, u64::MAX.into()),
+        ReprInt::U128 => (0, i128::MAX),
+    }
+}
+
+// Wraps `value` like an `as` cast would.
+fn cast(value: i128, repr_int: ReprInt) -> Option<i128> {
+    let value = match repr_int {
+        ReprInt::I8 => (value as i8).into(),
+        ReprInt::I16 => (value as i16).into(),
+        ReprInt::I32 => (value as i32).into(),
+        ReprInt::I64 | ReprInt::Isize => (value as i64).into(),
+        ReprInt::I128 => value,
+        ReprInt::U8 => (value as u8).into(),
+        ReprInt::U16 => (value as u16).into(),
+        ReprInt::U32 => (value as u32).into(),
+        ReprInt::U64 | ReprInt::Usize => (value as u64).into(),
+        ReprInt::U128 if value >= 0 => value,
+        ReprInt::U128 => return None,
+    };
+    Some(value)
+}
@@ -0,0 +1,47 @@
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::quote_spanned;
+
+/// An error pointing at some part of the macro's input.
+///
+/// Proc macros should report it with [`Error::to_compile_error`] rather than
+/// panicking, so that the error is displayed at the right place.
+#[derive(Clone, Debug)]
+pub struct Error {
+    span: Span,
+    message: String,
+}
+
+impl Error {
+    /// Creates an error with the given message, pointing at `span`.
+    pub fn new(span: Span, message: impl std::fmt::Display) -> Self {
+        Self {
+            span,
+            message: message.to_string(),
+        }
+    }
+
+    /// Returns the span the error points at.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Returns the error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns a `compile_error!("...")` invocation reporting this error.
+    pub fn to_compile_error(&self) -> TokenStream {
+        let mut message = Literal::string(&self.message);
+        message.set_span(self.span);
+        quote_spanned!(self.span=> ::core::compile_error!(#message);)
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,403 @@
+#![allow(missing_docs)]
+
+//! Owning transformation of declarations.
+//!
+//! [`Fold`] works like [`Visit`](crate::visit::Visit), but takes each node by
+//! value and returns the node that replaces it.
+//!
+//! ```
+//! # use venial::{fold::{self, Fold}, parse_declaration, Attribute, NamedField};
+//! # use quote::quote;
+//! struct AllowDeadCode;
+//!
+//! impl Fold for AllowDeadCode {
+//!     fn fold_named_field(&mut self, mut field: NamedField) -> NamedField {
+//!         field.attributes.push(Attribute::new("allow", quote!((dead_code))));
+//!         fold::walk_named_field(self, field)
+//!     }
+//! }
+//!
+//! let declaration = parse_declaration(quote!(
+//!     struct Hello {
+//!         a: u8,
+//!     }
+//! ));
+//! let declaration = AllowDeadCode.fold_declaration(declaration);
+//! assert_eq!(
+//!     quote!(#declaration).to_string(),
+//!     quote!(struct Hello { #[allow(dead_code)] a: u8, }).to_string(),
+//! );
+//! ```
+
+use crate::punctuated::{Punctuated, Separator};
+use crate::types::{
+    Attribute, Declaration, Enum, EnumDiscriminant, EnumVariant, ForBinder, Function,
+    FunctionParameter, FunctionQualifiers, GenericBound, GenericDefault, GenericParam,
+    GenericParams, Lifetime, LifetimePredicate, NamedField, NamedStructFields, Struct,
+    StructFields, TraitBound, TupleField, TupleStructFields, TyBound, TyExpr, TyPredicate, Union,
+    VisMarker, WhereClause, WhereClauseItem,
+};
+
+/// Transforms the nodes of a declaration by value.
+///
+/// See the [module documentation](self).
+pub trait Fold {
+    fn fold_declaration(&mut self, node: Declaration) -> Declaration {
+        walk_declaration(self, node)
+    }
+
+    fn fold_struct(&mut self, node: Struct) -> Struct {
+        walk_struct(self, node)
+    }
+
+    fn fold_enum(&mut self, node: Enum) -> Enum {
+        walk_enum(self, node)
+    }
+
+    fn fold_union(&mut self, node: Union) -> Union {
+        walk_union(self, node)
+    }
+
+    fn fold_function(&mut self, node: Function) -> Function {
+        walk_function(self, node)
+    }
+
+    fn fold_struct_fields(&mut self, node: StructFields) -> StructFields {
+        walk_struct_fields(self, node)
+    }
+
+    fn fold_named_field(&mut self, node: NamedField) -> NamedField {
+        walk_named_field(self, node)
+    }
+
+    fn fold_tuple_field(&mut self, node: TupleField) -> TupleField {
+        walk_tuple_field(self, node)
+    }
+
+    fn fold_enum_variant(&mut self, node: EnumVariant) -> EnumVariant {
+        walk_enum_variant(self, node)
+    }
+
+    fn fold_enum_discriminant(&mut self, node: EnumDiscriminant) -> EnumDiscriminant {
+        node
+    }
+
+    fn fold_function_qualifiers(&mut self, node: FunctionQualifiers) -> FunctionQualifiers {
+        node
+    }
+
+    fn fold_function_parameter(&mut self, node: FunctionParameter) -> FunctionParameter {
+        walk_function_parameter(self, node)
+    }
+
+    fn fold_attribute(&mut self, node: Attribute) -> Attribute {
+        node
+    }
+
+    fn fold_vis_marker(&mut self, node: VisMarker) -> VisMarker {
+        node
+    }
+
+    fn fold_generic_params(&mut self, node: GenericParams) -> GenericParams {
+        walk_generic_params(self, node)
+    }
+
+    fn fold_generic_param(&mut self, node: GenericParam) -> GenericParam {
+        walk_generic_param(self, node)
+    }
+
+    fn fold_generic_bound(&mut self, node: GenericBound) -> GenericBound {
+        node
+    }
+
+    fn fold_generic_default(&mut self, node: GenericDefault) -> GenericDefault {
+        node
+    }
+
+    fn fold_where_clause(&mut self, node: WhereClause) -> WhereClause {
+        walk_where_clause(self, node)
+    }
+
+    fn fold_where_clause_item(&mut self, node: WhereClauseItem) -> WhereClauseItem {
+        walk_where_clause_item(self, node)
+    }
+
+    fn fold_lifetime_predicate(&mut self, node: LifetimePredicate) -> LifetimePredicate {
+        walk_lifetime_predicate(self, node)
+    }
+
+    fn fold_ty_predicate(&mut self, node: TyPredicate) -> TyPredicate {
+        walk_ty_predicate(self, node)
+    }
+
+    fn fold_ty_bound(&mut self, node: TyBound) -> TyBound {
+        walk_ty_bound(self, node)
+    }
+
+    fn fold_trait_bound(&mut self, node: TraitBound) -> TraitBound {
+        walk_trait_bound(self, node)
+    }
+
+    fn fold_for_binder(&mut self, node: ForBinder) -> ForBinder {
+        walk_for_binder(self, node)
+    }
+
+    fn fold_lifetime(&mut self, node: Lifetime) -> Lifetime {
+        node
+    }
+
+    fn fold_ty_expr(&mut self, node: TyExpr) -> TyExpr {
+        node
+    }
+}
+
+pub fn walk_declaration<F: Fold + ?Sized>(folder: &mut F, node: Declaration) -> Declaration {
+    match node {
+        Declaration::Struct(struct_decl) => Declaration::Struct(folder.fold_struct(struct_decl)),
+        Declaration::Enum(enum_decl) => Declaration::Enum(folder.fold_enum(enum_decl)),
+        Declaration::Union(union_decl) => Declaration::Union(folder.fold_union(union_decl)),
+        Declaration::Function(function_decl) => {
+            Declaration::Function(folder.fold_function(function_decl))
+        }
+    }
+}
+
+pub fn walk_struct<F: Fold + ?Sized>(folder: &mut F, node: Struct) -> Struct {
+    Struct {
+        attributes: fold_attributes(folder, node.attributes),
+        vis_marker: node
+            .vis_marker
+            .map(|vis_marker| folder.fold_vis_marker(vis_marker)),
+        generic_params: node
+            .generic_params
+            .map(|generic_params| folder.fold_generic_params(generic_params)),
+        where_clause: node
+            .where_clause
+            .map(|where_clause| folder.fold_where_clause(where_clause)),
+        fields: folder.fold_struct_fields(node.fields),
+        ..node
+    }
+}
+
+pub fn walk_enum<F: Fold + ?Sized>(folder: &mut F, node: Enum) -> Enum {
+    Enum {
+        attributes: fold_attributes(folder, node.attributes),
+        vis_marker: node
+            .vis_marker
+            .map(|vis_marker| folder.fold_vis_marker(vis_marker)),
+        generic_params: node
+            .generic_params
+            .map(|generic_params| folder.fold_generic_params(generic_params)),
+        where_clause: node
+            .where_clause
+            .map(|where_clause| folder.fold_where_clause(where_clause)),
+        variants: fold_punctuated(node.variants, |variant| folder.fold_enum_variant(variant)),
+        ..node
+    }
+}
+
+pub fn walk_union<F: Fold + ?Sized>(folder: &mut F, node: Union) -> Union {
+    Union {
+        attributes: fold_attributes(folder, node.attributes),
+        vis_marker: node
+            .vis_marker
+            .map(|vis_marker| folder.fold_vis_marker(vis_marker)),
+        generic_params: node
+            .generic_params
+            .map(|generic_params| folder.fold_generic_params(generic_params)),
+        where_clause: node
+            .where_clause
+            .map(|where_clause| folder.fold_where_clause(where_clause)),
+        fields: NamedStructFields {
+            fields: fold_punctuated(node.fields.fields, |field| folder.fold_named_field(field)),
+            ..node.fields
+        },
+        ..node
+    }
+}
+
+pub fn walk_function<F: Fold + ?Sized>(folder: &mut F, node: Function) -> Function {
+    Function {
+        attributes: fold_attributes(folder, node.attributes),
+        vis_marker: node
+            .vis_marker
+            .map(|vis_marker| folder.fold_vis_marker(vis_marker)),
+        qualifiers: folder.fold_function_qualifiers(node.qualifiers),
+        generic_params: node
+            .generic_params
+            .map(|generic_params| folder.fold_generic_params(generic_params)),
+        params: fold_punctuated(node.params, |param| folder.fold_function_parameter(param)),
+        return_ty: node
+            .return_ty
+            .map(|return_ty| folder.fold_ty_expr(return_ty)),
+        where_clause: node
+            .where_clause
+            .map(|where_clause| folder.fold_where_clause(where_clause)),
+        ..node
+    }
+}
+
+pub fn walk_struct_fields<F: Fold + ?Sized>(folder: &mut F, node: StructFields) -> StructFields {
+    match node {
+        StructFields::Unit => StructFields::Unit,
+        StructFields::Tuple(tuple_fields) => StructFields::Tuple(TupleStructFields {
+            fields: fold_punctuated(tuple_fields.fields, |field| folder.fold_tuple_field(field)),
+            ..tuple_fields
+        }),
+        StructFields::Named(named_fields) => StructFields::Named(NamedStructFields {
+            fields: fold_punctuated(named_fields.fields, |field| folder.fold_named_field(field)),
+            ..named_fields
+        }),
+    }
+}
+
+pub fn walk_named_field<F: Fold + ?Sized>(folder: &mut F, node: NamedField) -> NamedField {
+    NamedField {
+        attributes: fold_attributes(folder, node.attributes),
+        vis_marker: node
+            .vis_marker
+            .map(|vis_marker| folder.fold_vis_marker(vis_marker)),
+        ty: folder.fold_ty_expr(node.ty),
+        ..node
+    }
+}
+
+pub fn walk_tuple_field<F: Fold + ?Sized>(folder: &mut F, node: TupleField) -> TupleField {
+    TupleField {
+        attributes: fold_attributes(folder, node.attributes),
+        vis_marker: node
+            .vis_marker
+            .map(|vis_marker| folder.fold_vis_marker(vis_marker)),
+        ty: folder.fold_ty_expr(node.ty),
+    }
+}
+
+pub fn walk_enum_variant<F: Fold + ?Sized>(folder: &mut F, node: EnumVariant) -> EnumVariant {
+    EnumVariant {
+        attributes: fold_attributes(folder, node.attributes),
+        vis_marker: node
+            .vis_marker
+            .map(|vis_marker| folder.fold_vis_marker(vis_marker)),
+        contents: folder.fold_struct_fields(node.contents),
+        discriminant: node
+            .discriminant
+            .map(|discriminant| folder.fold_enum_discriminant(discriminant)),
+        ..node
+    }
+}
+
+pub fn walk_function_parameter<F: Fold + ?Sized>(
+    folder: &mut F,
+    node: FunctionParameter,
+) -> FunctionParameter {
+    FunctionParameter {
+        attributes: fold_attributes(folder, node.attributes),
+        ty: folder.fold_ty_expr(node.ty),
+        ..node
+    }
+}
+
+pub fn walk_generic_params<F: Fold + ?Sized>(folder: &mut F, node: GenericParams) -> GenericParams {
+    GenericParams {
+        params: fold_punctuated(node.params, |param| folder.fold_generic_param(param)),
+        ..node
+    }
+}
+
+pub fn walk_generic_param<F: Fold + ?Sized>(folder: &mut F, node: GenericParam) -> GenericParam {
+    GenericParam {
+        attributes: fold_attributes(folder, node.attributes),
+        bound: node.bound.map(|bound| folder.fold_generic_bound(bound)),
+        default: node
+            .default
+            .map(|default| folder.fold_generic_default(default)),
+        ..node
+    }
+}
+
+pub fn walk_where_clause<F: Fold + ?Sized>(folder: &mut F, node: WhereClause) -> WhereClause {
+    WhereClause {
+        items: fold_punctuated(node.items, |item| folder.fold_where_clause_item(item)),
+        ..node
+    }
+}
+
+pub fn walk_where_clause_item<F: Fold + ?Sized>(
+    folder: &mut F,
+    node: WhereClauseItem,
+) -> WhereClauseItem {
+    match node {
+        WhereClauseItem::Lifetime(predicate) => {
+            WhereClauseItem::Lifetime(folder.fold_lifetime_predicate(predicate))
+        }
+        WhereClauseItem::Ty(predicate) => WhereClauseItem::Ty(folder.fold_ty_predicate(predicate)),
+    }
+}
+
+pub fn walk_lifetime_predicate<F: Fold + ?Sized>(
+    folder: &mut F,
+    node: LifetimePredicate,
+) -> LifetimePredicate {
+    LifetimePredicate {
+        lifetime: folder.fold_lifetime(node.lifetime),
+        bounds: fold_punctuated(node.bounds, |bound| folder.fold_lifetime(bound)),
+        ..node
+    }
+}
+
+pub fn walk_ty_predicate<F: Fold + ?Sized>(folder: &mut F, node: TyPredicate) -> TyPredicate {
+    TyPredicate {
+        for_binder: node
+            .for_binder
+            .map(|for_binder| folder.fold_for_binder(for_binder)),
+        left_side: folder.fold_ty_expr(node.left_side),
+        bounds: fold_punctuated(node.bounds, |bound| folder.fold_ty_bound(bound)),
+        ..node
+    }
+}
+
+pub fn walk_ty_bound<F: Fold + ?Sized>(folder: &mut F, node: TyBound) -> TyBound {
+    match node {
+        TyBound::Lifetime(lifetime) => TyBound::Lifetime(folder.fold_lifetime(lifetime)),
+        TyBound::Trait(trait_bound) => TyBound::Trait(folder.fold_trait_bound(trait_bound)),
+    }
+}
+
+pub fn walk_trait_bound<F: Fold + ?Sized>(folder: &mut F, node: TraitBound) -> TraitBound {
+    TraitBound {
+        for_binder: node
+            .for_binder
+            .map(|for_binder| folder.fold_for_binder(for_binder)),
+        path: folder.fold_ty_expr(node.path),
+        ..node
+    }
+}
+
+pub fn walk_for_binder<F: Fold + ?Sized>(folder: &mut F, node: ForBinder) -> ForBinder {
+    ForBinder {
+        params: folder.fold_generic_params(node.params),
+        ..node
+    }
+}
+
+fn fold_attributes<F: Fold + ?Sized>(folder: &mut F, attributes: Vec<Attribute>) -> Vec<Attribute> {
+    attributes
+        .into_iter()
+        .map(|attribute| folder.fold_attribute(attribute))
+        .collect()
+}
+
+// Keeps the separators of `items` as they are.
+fn fold_punctuated<T, P: Separator>(
+    items: Punctuated<T, P>,
+    mut f: impl FnMut(T) -> T,
+) -> Punctuated<T, P> {
+    let mut folded = Punctuated::new();
+    folded.skip_last = items.skip_last;
+    folded.inner = items
+        .inner
+        .into_iter()
+        .map(|(item, punct)| (f(item), punct))
+        .collect();
+    folded
+}
@@ -0,0 +1,104 @@
+use crate::types::{
+    Declaration, Enum, EnumVariant, Field, Function, FunctionParameter, GenericParam, NamedField,
+    Struct, Union,
+};
+use proc_macro2::Ident;
+
+/// Extension methods for [`Ident`].
+pub trait IdentExt {
+    /// Returns true if this is a raw identifier, eg `r#type`.
+    fn is_raw(&self) -> bool;
+
+    /// Returns the identifier without its `r#` prefix, with the same span.
+    ///
+    /// The result may be a keyword, so it should be used to build strings,
+    /// eg serialized names or parts of generated names, rather than printed
+    /// as-is.
+    ///
+    /// ```
+    /// # use venial::IdentExt;
+    /// # use proc_macro2::TokenTree;
+    /// # use quote::quote;
+    /// let ident = match quote!(r#type).into_iter().next() {
+    ///     Some(TokenTree::Ident(ident)) => ident,
+    ///     _ => unreachable!(),
+    /// };
+    /// assert_eq!(ident.to_string(), "r#type");
+    /// assert_eq!(ident.unraw().to_string(), "type");
+    /// ```
+    fn unraw(&self) -> Ident;
+}
+
+impl IdentExt for Ident {
+    fn is_raw(&self) -> bool {
+        self.to_string().starts_with("r#")
+    }
+
+    fn unraw(&self) -> Ident {
+        let name = self.to_string();
+        match name.strip_prefix("r#") {
+            Some(name) => Ident::new(name, self.span()),
+            None => self.clone(),
+        }
+    }
+}
+
+macro_rules! impl_name_unraw {
+    ($($ty:ty),*) => {
+        $(
+            impl $ty {
+                /// Returns the name without its `r#` prefix, if it's a raw
+                /// identifier. See [`IdentExt::unraw`].
+                pub fn name_unraw(&self) -> Ident {
+                    self.name.unraw()
+                }
+            }
+        )*
+    };
+}
+
+impl_name_unraw!(
+    Struct,
+    Enum,
+    Union,
+    Function,
+    EnumVariant,
+    NamedField,
+    FunctionParameter,
+    GenericParam
+);
+
+impl Declaration {
+    /// Returns the name without its `r#` prefix, if it's a raw identifier.
+    /// See [`IdentExt::unraw`].
+    pub fn name_unraw(&self) -> Ident {
+        self.name().unraw()
+    }
+}
+
+impl<'a> Field<'a> {
+    /// Returns the field's name without its `r#` prefix, or None for tuple
+    /// fields. See [`IdentExt::unraw`].
+    pub fn name_unraw(&self) -> Option<Ident> {
+        self.name.map(IdentExt::unraw)
+    }
+}
+
+// Strict and reserved keywords of the 2015 edition, which are keywords in every
+// edition and so can only be used as names in their raw form.
+//
+// Keywords added by later editions (`async`, `await`, `dyn`, `try`, `gen`) are
+// left out, as venial can't tell which edition the tokens come from. Weak
+// keywords like `union` or `default` are valid names.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "become", "box", "break", "const", "continue", "crate", "do", "else", "enum",
+    "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop", "macro", "match",
+    "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "self", "Self", "static",
+    "struct", "super", "trait", "true", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+    "where", "while", "yield",
+];
+
+pub(crate) fn is_keyword(ident: &Ident) -> bool {
+    let name = ident.to_string();
+    KEYWORDS.contains(&name.as_str())
+}
@@ -58,19 +58,39 @@ pub mod fuzz;
 
 // TODO - Add CI
 
+mod attributes;
+mod bindings;
+mod builders;
+mod case;
+mod cfg;
+mod discriminants;
+mod error;
+pub mod fold;
+mod idents;
+mod mirror;
+mod name_gen;
 mod parse;
 mod punctuated;
+mod repr;
+mod spans;
+mod substitution;
 mod types;
 mod types_edition;
+pub mod visit;
+pub mod visit_mut;
 
 #[cfg(test)]
 mod tests;
 
+pub use error::Error;
+pub use idents::IdentExt;
 pub use parse::parse_declaration;
 
-pub use punctuated::Punctuated;
+pub use punctuated::{Comma, Or, Plus, Punctuated, Semi, Separator};
+pub use spans::Spanned;
 pub use types::{
-    Attribute, Declaration, Enum, EnumDiscriminant, EnumVariant, GenericBound, GenericParam, GenericParams, NamedField,
-    NamedStructFields, Struct, StructFields, TupleField, TupleStructFields, TyExpr, Union, VisMarker, WhereClause,
+    Attribute, Binding, BindingMode, CfgOptions, CfgPredicate, Declaration, Deprecated, DiscriminantValue, Enum, EnumDiscriminant, EnumVariant, Field, ForBinder, Function, FunctionParameter, FunctionQualifiers, GenericBound, GenericDefault, GenericParam,
+    GenericParams, ImplGenerics, ImplTraitOptions, Lifetime, LifetimePredicate, LintLevel, Member, NameGen, NamedField, NamedStructFields, Repr, RenameRule, ReprInt, ReprKind, Struct, StructFields,
+    TraitBound, TupleField, TupleStructFields, TyBound, TyExpr, TyGenerics, TyPredicate, Union, VisMarker, WhereClause,
     WhereClauseItem,
 };
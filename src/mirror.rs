@@ -0,0 +1,449 @@
+use crate::types::{
+    Attribute, Enum, EnumVariant, Field, GenericParam, GenericParams, NamedField,
+    NamedStructFields, Struct, StructFields, TupleField, TupleStructFields, TyExpr, WhereClause,
+};
+use crate::Punctuated;
+use proc_macro2::{Ident, TokenStream, TokenTree};
+use quote::ToTokens;
+
+impl Struct {
+    /// Returns a struct with the same shape, where each field has the type
+    /// returned by `f`, or is removed if `f` returns None.
+    ///
+    /// The new struct is called `name`. It keeps the visibility, generic
+    /// parameters and where clause of this struct, minus the parameters which
+    /// the new fields don't use (along with the bounds and where clause items
+    /// mentioning them). They can then be adjusted with eg
+    /// [`Struct::with_param`]. Only `#[doc]` and `#[cfg]` attributes are kept,
+    /// on the struct and its fields; see [`Struct::map_fields_with`] to keep
+    /// others.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, GenericParam};
+    /// # use quote::quote;
+    /// let declaration = parse_declaration(quote!(
+    ///     #[derive(Debug)]
+    ///     struct Foo<T> {
+    ///         /// The A
+    ///         a: T,
+    ///         #[serde(skip)]
+    ///         b: String,
+    ///     }
+    /// ));
+    /// let foo_ref = declaration
+    ///     .as_struct()
+    ///     .unwrap()
+    ///     .map_fields("FooRef", |field| {
+    ///         let ty = field.ty;
+    ///         Some(quote!(&'a #ty))
+    ///     })
+    ///     .with_param(GenericParam::lifetime("a"));
+    /// assert_eq!(
+    ///     quote!(#foo_ref).to_string(),
+    ///     quote!(struct FooRef<'a, T> {
+    ///         /// The A
+    ///         a: &'a T,
+    ///         b: &'a String,
+    ///     }).to_string(),
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid identifier.
+    pub fn map_fields<T: ToTokens>(
+        &self,
+        name: &str,
+        f: impl FnMut(Field<'_>) -> Option<T>,
+    ) -> Struct {
+        self.map_fields_with(name, |_attribute| false, f)
+    }
+
+    /// Like [`Struct::map_fields`], but also keeps the attributes for which
+    /// `keep_attribute` returns true, on the struct and its fields.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let declaration = parse_declaration(quote!(
+    ///     #[derive(Debug)]
+    ///     #[serde(rename_all = "camelCase")]
+    ///     struct Foo {
+    ///         #[serde(skip)]
+    ///         foo_bar: u8,
+    ///     }
+    /// ));
+    /// let foo_patch = declaration
+    ///     .as_struct()
+    ///     .unwrap()
+    ///     .map_fields_with("FooPatch", |attribute| attribute.has_path("serde"), |field| {
+    ///         let ty = field.ty;
+    ///         Some(quote!(Option<#ty>))
+    ///     });
+    /// assert_eq!(
+    ///     quote!(#foo_patch).to_string(),
+    ///     quote!(
+    ///         #[serde(rename_all = "camelCase")]
+    ///         struct FooPatch {
+    ///             #[serde(skip)]
+    ///             foo_bar: Option<u8>,
+    ///         }
+    ///     ).to_string(),
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid identifier.
+    pub fn map_fields_with<T: ToTokens>(
+        &self,
+        name: &str,
+        keep_attribute: impl Fn(&Attribute) -> bool,
+        f: impl FnMut(Field<'_>) -> Option<T>,
+    ) -> Struct {
+        let fields = self.fields.map_fields_with(&keep_attribute, f);
+        let (generic_params, where_clause) = used_generics(
+            self.generic_params.as_ref(),
+            self.where_clause.as_ref(),
+            fields.iter().map(|field| field.ty),
+        );
+
+        Struct {
+            attributes: mirrored_attributes(&self.attributes, &keep_attribute),
+            vis_marker: self.vis_marker.clone(),
+            _struct: self._struct.clone(),
+            name: Ident::new(name, self.name.span()),
+            generic_params,
+            where_clause,
+            fields,
+            _semicolon: self._semicolon.clone(),
+        }
+    }
+}
+
+impl Enum {
+    /// Returns an enum with the variants returned by `f`, skipping the variants
+    /// for which `f` returns None.
+    ///
+    /// The new enum is called `name`, and keeps the visibility, generic
+    /// parameters and where clause of this enum, minus the parameters which
+    /// the new variants don't use, like [`Struct::map_fields`]. Only `#[doc]`
+    /// and `#[cfg]` attributes are kept on the enum, as well as `#[repr]`
+    /// attributes if one of the new variants has an explicit discriminant; see
+    /// [`Enum::map_variants_with`] to keep others. [`EnumVariant::map_fields`]
+    /// can be used to build variants with the same shape.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let declaration = parse_declaration(quote!(
+    ///     enum Message {
+    ///         Quit,
+    ///         Move { x: i32, y: i32 },
+    ///         Write(String),
+    ///     }
+    /// ));
+    /// let patch = declaration
+    ///     .as_enum()
+    ///     .unwrap()
+    ///     .map_variants("MessagePatch", |variant| {
+    ///         if variant.contents.is_unit() {
+    ///             return None;
+    ///         }
+    ///         Some(variant.map_fields(|field| {
+    ///             let ty = field.ty;
+    ///             Some(quote!(Option<#ty>))
+    ///         }))
+    ///     });
+    /// assert_eq!(
+    ///     quote!(#patch).to_string(),
+    ///     quote!(enum MessagePatch {
+    ///         Move { x: Option<i32>, y: Option<i32> },
+    ///         Write(Option<String>),
+    ///     }).to_string(),
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid identifier.
+    pub fn map_variants(
+        &self,
+        name: &str,
+        f: impl FnMut(&EnumVariant) -> Option<EnumVariant>,
+    ) -> Enum {
+        self.map_variants_with(name, |_attribute| false, f)
+    }
+
+    /// Like [`Enum::map_variants`], but also keeps the enum's attributes for
+    /// which `keep_attribute` returns true. The attributes of the variants are
+    /// those returned by `f`, eg with [`EnumVariant::map_fields_with`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't a valid identifier.
+    pub fn map_variants_with(
+        &self,
+        name: &str,
+        keep_attribute: impl Fn(&Attribute) -> bool,
+        mut f: impl FnMut(&EnumVariant) -> Option<EnumVariant>,
+    ) -> Enum {
+        let variants = map_punctuated(&self.variants, |_index, variant| f(variant));
+
+        // Discriminants on enums with fields require a primitive representation.
+        let has_discriminants = variants
+            .iter()
+            .any(|variant| variant.discriminant.is_some());
+        let attributes = self
+            .attributes
+            .iter()
+            .filter(|attribute| {
+                is_mirrored(attribute, &keep_attribute)
+                    || (has_discriminants && attribute.has_path("repr"))
+            })
+            .cloned()
+            .collect();
+        let (generic_params, where_clause) = used_generics(
+            self.generic_params.as_ref(),
+            self.where_clause.as_ref(),
+            variants
+                .iter()
+                .flat_map(|variant| variant.contents.iter())
+                .map(|field| field.ty),
+        );
+
+        Enum {
+            attributes,
+            vis_marker: self.vis_marker.clone(),
+            _enum: self._enum.clone(),
+            name: Ident::new(name, self.name.span()),
+            generic_params,
+            where_clause,
+            tk_braces: self.tk_braces.clone(),
+            variants,
+        }
+    }
+}
+
+impl EnumVariant {
+    /// Returns a variant with the same name and shape, where each field has the
+    /// type returned by `f`, or is removed if `f` returns None.
+    ///
+    /// Only `#[doc]` and `#[cfg]` attributes are kept, on the variant and its
+    /// fields. See [`Enum::map_variants`].
+    pub fn map_fields<T: ToTokens>(&self, f: impl FnMut(Field<'_>) -> Option<T>) -> EnumVariant {
+        self.map_fields_with(|_attribute| false, f)
+    }
+
+    /// Like [`EnumVariant::map_fields`], but also keeps the attributes for
+    /// which `keep_attribute` returns true, on the variant and its fields.
+    pub fn map_fields_with<T: ToTokens>(
+        &self,
+        keep_attribute: impl Fn(&Attribute) -> bool,
+        f: impl FnMut(Field<'_>) -> Option<T>,
+    ) -> EnumVariant {
+        EnumVariant {
+            attributes: mirrored_attributes(&self.attributes, &keep_attribute),
+            vis_marker: self.vis_marker.clone(),
+            name: self.name.clone(),
+            contents: self.contents.map_fields_with(&keep_attribute, f),
+            discriminant: self.discriminant.clone(),
+        }
+    }
+}
+
+impl StructFields {
+    /// Returns fields with the same shape, where each field has the type
+    /// returned by `f`, or is removed if `f` returns None.
+    ///
+    /// Fields keep their name and visibility, and only their `#[doc]` and
+    /// `#[cfg]` attributes.
+    pub fn map_fields<T: ToTokens>(&self, f: impl FnMut(Field<'_>) -> Option<T>) -> Self {
+        self.map_fields_with(|_attribute| false, f)
+    }
+
+    /// Like [`StructFields::map_fields`], but also keeps the attributes for
+    /// which `keep_attribute` returns true.
+    pub fn map_fields_with<T: ToTokens>(
+        &self,
+        keep_attribute: impl Fn(&Attribute) -> bool,
+        mut f: impl FnMut(Field<'_>) -> Option<T>,
+    ) -> Self {
+        match self {
+            StructFields::Unit => StructFields::Unit,
+            StructFields::Tuple(tuple_fields) => StructFields::Tuple(TupleStructFields {
+                fields: map_punctuated(&tuple_fields.fields, |index, field| {
+                    let ty = f(Field {
+                        index,
+                        name: None,
+                        attributes: &field.attributes,
+                        vis_marker: field.vis_marker.as_ref(),
+                        ty: &field.ty,
+                    })?;
+                    Some(TupleField {
+                        attributes: mirrored_attributes(&field.attributes, &keep_attribute),
+                        vis_marker: field.vis_marker.clone(),
+                        ty: to_ty_expr(ty),
+                    })
+                }),
+                tk_parens: tuple_fields.tk_parens.clone(),
+            }),
+            StructFields::Named(named_fields) => StructFields::Named(NamedStructFields {
+                fields: map_punctuated(&named_fields.fields, |index, field| {
+                    let ty = f(Field {
+                        index,
+                        name: Some(&field.name),
+                        attributes: &field.attributes,
+                        vis_marker: field.vis_marker.as_ref(),
+                        ty: &field.ty,
+                    })?;
+                    Some(NamedField {
+                        attributes: mirrored_attributes(&field.attributes, &keep_attribute),
+                        vis_marker: field.vis_marker.clone(),
+                        name: field.name.clone(),
+                        _colon: field._colon.clone(),
+                        ty: to_ty_expr(ty),
+                    })
+                }),
+                tk_braces: named_fields.tk_braces.clone(),
+            }),
+        }
+    }
+}
+
+// Attributes like `#[derive]` or `#[serde]` are specific to the original
+// type, so by default mirror types only keep documentation and conditional
+// compilation.
+fn is_mirrored(attribute: &Attribute, keep_attribute: impl Fn(&Attribute) -> bool) -> bool {
+    attribute.has_path("doc") || attribute.is_cfg() || keep_attribute(attribute)
+}
+
+fn mirrored_attributes(
+    attributes: &[Attribute],
+    keep_attribute: impl Fn(&Attribute) -> bool,
+) -> Vec<Attribute> {
+    attributes
+        .iter()
+        .filter(|attribute| is_mirrored(attribute, &keep_attribute))
+        .cloned()
+        .collect()
+}
+
+// Rustc rejects unused parameters, so parameters which only the original
+// fields used are removed, along with the bounds, defaults and where clause
+// items which mention them.
+fn used_generics<'a>(
+    generic_params: Option<&GenericParams>,
+    where_clause: Option<&WhereClause>,
+    field_tys: impl Iterator<Item = &'a TyExpr>,
+) -> (Option<GenericParams>, Option<WhereClause>) {
+    let generic_params = match generic_params {
+        Some(generic_params) => generic_params,
+        None => return (None, where_clause.cloned()),
+    };
+    let field_tys: Vec<&TyExpr> = field_tys.collect();
+    let is_used = |param: &GenericParam| {
+        field_tys
+            .iter()
+            .any(|ty| mentions(ty.to_token_stream(), param))
+    };
+    let unused_params: Vec<&GenericParam> = generic_params
+        .params
+        .items()
+        .filter(|param| !is_used(param))
+        .collect();
+    if unused_params.is_empty() {
+        return (Some(generic_params.clone()), where_clause.cloned());
+    }
+    let mentions_unused = |tokens: TokenStream| {
+        unused_params
+            .iter()
+            .any(|param| mentions(tokens.clone(), param))
+    };
+
+    let params = map_punctuated(&generic_params.params, |_index, param| {
+        if !is_used(param) {
+            return None;
+        }
+        let mut param = param.clone();
+        if matches!(&param.bound, Some(bound) if mentions_unused(bound.to_token_stream())) {
+            param.bound = None;
+        }
+        if matches!(&param.default, Some(default) if mentions_unused(default.to_token_stream())) {
+            param.default = None;
+        }
+        Some(param)
+    });
+    let generic_params = if params.is_empty() {
+        None
+    } else {
+        Some(GenericParams {
+            params,
+            ..generic_params.clone()
+        })
+    };
+
+    let where_clause = where_clause.and_then(|where_clause| {
+        let items = map_punctuated(&where_clause.items, |_index, item| {
+            if mentions_unused(item.to_token_stream()) {
+                None
+            } else {
+                Some(item.clone())
+            }
+        });
+        if items.is_empty() {
+            None
+        } else {
+            Some(WhereClause {
+                items,
+                ..where_clause.clone()
+            })
+        }
+    });
+
+    (generic_params, where_clause)
+}
+
+// Returns true if `param` appears in `tokens`, other than as a path segment
+// (eg `a::T`). Lifetimes only match after an apostrophe, and other
+// parameters never do.
+fn mentions(tokens: TokenStream, param: &GenericParam) -> bool {
+    let mut prev_token: Option<TokenTree> = None;
+    for token in tokens {
+        match &token {
+            TokenTree::Group(group) if mentions(group.stream(), param) => return true,
+            TokenTree::Ident(ident) if *ident == param.name => {
+                let prev_char = match &prev_token {
+                    Some(TokenTree::Punct(punct)) => Some(punct.as_char()),
+                    _ => None,
+                };
+                if prev_char != Some(':') && (prev_char == Some('\'')) == param.is_lifetime() {
+                    return true;
+                }
+            }
+            _ => (),
+        }
+        prev_token = Some(token);
+    }
+    false
+}
+
+// Keeps the separator following each kept element.
+fn map_punctuated<T, U>(
+    items: &Punctuated<T>,
+    mut f: impl FnMut(usize, &T) -> Option<U>,
+) -> Punctuated<U> {
+    let mut mapped = Punctuated::new();
+    for (index, (item, punct)) in items.pairs().enumerate() {
+        if let Some(new_item) = f(index, item) {
+            mapped.push(new_item, punct.cloned());
+        }
+    }
+    mapped
+}
+
+fn to_ty_expr(ty: impl ToTokens) -> TyExpr {
+    TyExpr {
+        tokens: ty.into_token_stream().into_iter().collect(),
+    }
+}
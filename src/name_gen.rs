@@ -0,0 +1,136 @@
+use crate::types::{Declaration, GenericParam, GenericParams, NameGen};
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use quote::ToTokens;
+
+impl NameGen {
+    /// Creates a generator which avoids every identifier used in `declaration`:
+    /// its name, generic parameters, fields, variants, and the identifiers in
+    /// types and attributes.
+    ///
+    /// Identifiers are created with [`Span::mixed_site`] by default, so that
+    /// generated local variables can't refer to the user's.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, NameGen};
+    /// # use quote::quote;
+    /// let declaration = parse_declaration(quote!(
+    ///     struct Hello<__T>(__T, __value);
+    /// ));
+    /// let mut name_gen = NameGen::new(&declaration);
+    /// assert_eq!(name_gen.ty_param("T").name, "__T1");
+    /// assert_eq!(name_gen.ty_param("T").name, "__T2");
+    /// assert_eq!(name_gen.ident("value"), "__value1");
+    /// assert_eq!(name_gen.lifetime("a").name, "__a");
+    /// ```
+    pub fn new(declaration: &Declaration) -> Self {
+        let mut name_gen = NameGen {
+            used_names: Vec::new(),
+            span: Span::mixed_site(),
+        };
+        name_gen.reserve_tokens(declaration.to_token_stream());
+        name_gen
+    }
+
+    /// Creates a generator which avoids every identifier used in
+    /// `generic_params`, eg the result of [`GenericParams::merge`] when a
+    /// wrapper combines the generics of several declarations.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, NameGen};
+    /// # use quote::quote;
+    /// let declaration = parse_declaration(quote!(
+    ///     struct Hello<'__a, __T: Into<__U>, __U>(__T, &'__a __U);
+    /// ));
+    /// let mut name_gen = NameGen::from_generic_params(declaration.generic_params().unwrap());
+    /// assert_eq!(name_gen.ty_param("T").name, "__T1");
+    /// assert_eq!(name_gen.ty_param("U").name, "__U1");
+    /// assert_eq!(name_gen.lifetime("a").name, "__a1");
+    /// ```
+    pub fn from_generic_params(generic_params: &GenericParams) -> Self {
+        let mut name_gen = NameGen {
+            used_names: Vec::new(),
+            span: Span::mixed_site(),
+        };
+        name_gen.reserve_tokens(generic_params.to_token_stream());
+        name_gen
+    }
+
+    /// Sets the span of the generated identifiers, eg [`Span::call_site`] for
+    /// identifiers the user should be able to refer to.
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+
+    /// Marks `name` as used, so that it won't be generated.
+    pub fn reserve(&mut self, name: &str) {
+        self.used_names.push(name.to_string());
+    }
+
+    /// Returns a fresh identifier for a local variable or an item, eg `__value`
+    /// for `ident("value")`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hint` isn't a valid identifier.
+    pub fn ident(&mut self, hint: &str) -> Ident {
+        let name = self.fresh_name(hint);
+        Ident::new(&name, self.span)
+    }
+
+    /// Returns a fresh type parameter, eg `__T` for `ty_param("T")`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hint` isn't a valid identifier.
+    pub fn ty_param(&mut self, hint: &str) -> GenericParam {
+        let mut param = GenericParam::ty("T");
+        param.name = self.ident(hint);
+        param
+    }
+
+    /// Returns a fresh lifetime parameter, eg `'__a` for `lifetime("a")`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hint` isn't a valid identifier.
+    pub fn lifetime(&mut self, hint: &str) -> GenericParam {
+        let mut param = GenericParam::lifetime("a");
+        param.name = self.ident(hint);
+        param
+    }
+
+    // Lifetimes, types and values are in different namespaces, but we keep
+    // a single list of names to make generated code easier to read.
+    fn fresh_name(&mut self, hint: &str) -> String {
+        let base = format!("__{}", hint);
+        let mut name = base.clone();
+        let mut i = 0;
+        while self.is_used(&name) {
+            i += 1;
+            name = format!("{}{}", base, i);
+        }
+        self.used_names.push(name.clone());
+        name
+    }
+
+    fn is_used(&self, name: &str) -> bool {
+        self.used_names.iter().any(|used_name| used_name == name)
+    }
+
+    fn reserve_tokens(&mut self, tokens: TokenStream) {
+        for token in tokens {
+            match token {
+                TokenTree::Ident(ident) => {
+                    let name = ident.to_string();
+                    let name = name.strip_prefix("r#").unwrap_or(&name);
+                    if !self.is_used(name) {
+                        self.reserve(name);
+                    }
+                }
+                TokenTree::Group(group) => self.reserve_tokens(group.stream()),
+                TokenTree::Punct(_) | TokenTree::Literal(_) => (),
+            }
+        }
+    }
+}
@@ -1,13 +1,15 @@
 use crate::{
+    idents::is_keyword,
+    punctuated::{Comma, Plus, Punctuated, Separator},
     types::{
-        Attribute, Declaration, Enum, EnumDiscriminant, EnumVariant, Function, FunctionParameter,
-        FunctionQualifiers, GenericBound, GenericParam, GenericParams, NamedField,
-        NamedStructFields, Struct, StructFields, TupleField, TupleStructFields, TyExpr, Union,
-        VisMarker, WhereClause, WhereClauseItem,
+        Attribute, Declaration, Enum, EnumDiscriminant, EnumVariant, ForBinder, Function,
+        FunctionParameter, FunctionQualifiers, GenericBound, GenericDefault, GenericParam,
+        GenericParams, Lifetime, LifetimePredicate, NamedField, NamedStructFields, Struct,
+        StructFields, TraitBound, TupleField, TupleStructFields, TyBound, TyExpr, TyPredicate,
+        Union, VisMarker, WhereClause, WhereClauseItem,
     },
-    Punctuated,
 };
-use proc_macro2::{Delimiter, Group, Ident, Punct, TokenStream, TokenTree};
+use proc_macro2::{Delimiter, Group, Ident, Punct, Spacing, TokenStream, TokenTree};
 use std::iter::Peekable;
 
 type TokenIter = Peekable<proc_macro2::token_stream::IntoIter>;
@@ -77,18 +79,44 @@ fn consume_declaration_name(tokens: &mut TokenIter) -> Ident {
     let token = tokens
         .next()
         .expect("cannot parse declaration: expected identifier, found end-of-stream");
-    parse_ident(token).unwrap_or_else(|token| {
+    let ident = parse_ident(token).unwrap_or_else(|token| {
         panic!(
             "cannot parse declaration: expected identifier, found token {:?}",
             token
         );
-    })
+    });
+    if is_keyword(&ident) {
+        panic!(
+            "cannot parse declaration: expected identifier, found keyword `{}` (use `r#{}` for a raw identifier)",
+            ident, ident
+        );
+    }
+    ident
+}
+
+// Returns true if the next two tokens are a `::` path separator
+fn starts_with_path_separator(tokens: &mut TokenIter) -> bool {
+    match tokens.peek() {
+        Some(TokenTree::Punct(punct))
+            if punct.as_char() == ':' && punct.spacing() == Spacing::Joint => {}
+        _ => return false,
+    };
+
+    let mut lookahead = tokens.clone();
+    lookahead.next();
+    match lookahead.next() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => true,
+        _ => false,
+    }
 }
 
 // Consumes tokens until a separator is reached *unless* the
 // separator in between angle brackets
 // eg consume_stuff_until(..., ',') will consume all
 // of `Foobar<A, B>,` except for the last comma
+//
+// `::` path separators are always consumed, so a ':' predicate
+// will stop at the colon of `T::Item: Clone`, not inside the path.
 pub(crate) fn consume_stuff_until(
     tokens: &mut TokenIter,
     predicate: impl FnMut(&TokenTree) -> bool,
@@ -101,6 +129,13 @@ pub(crate) fn consume_stuff_until(
     // TODO - handle closures
 
     loop {
+        if starts_with_path_separator(tokens) {
+            output_tokens.push(tokens.next().unwrap());
+            output_tokens.push(tokens.next().unwrap());
+            prev_token_is_dash = false;
+            continue;
+        }
+
         let token = tokens.peek();
         prev_token_is_dash = match &token {
             Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {
@@ -133,9 +168,10 @@ pub(crate) fn consume_stuff_until(
     output_tokens
 }
 
-fn consume_period(tokens: &mut TokenIter) -> Option<Punct> {
+// Consumes a `P` separator, if there is one.
+fn consume_separator<P: Separator>(tokens: &mut TokenIter) -> Option<Punct> {
     match tokens.peek() {
-        Some(TokenTree::Punct(punct)) if punct.as_char() == ',' => {
+        Some(TokenTree::Punct(punct)) if P::is(punct) => {
             let punct = punct.clone();
             tokens.next().unwrap();
             Some(punct)
@@ -160,6 +196,8 @@ fn consume_generic_params(tokens: &mut TokenIter) -> Option<GenericParams> {
     tokens.next();
 
     loop {
+        let attributes = consume_attributes(tokens);
+
         let token = tokens
             .peek()
             .expect("cannot parse generic params: expected token after '>'");
@@ -186,6 +224,7 @@ fn consume_generic_params(tokens: &mut TokenIter) -> Option<GenericParams> {
 
                 let bound_tokens = consume_stuff_until(tokens, |token| match token {
                     TokenTree::Punct(punct) if punct.as_char() == ',' => true,
+                    TokenTree::Punct(punct) if punct.as_char() == '=' => true,
                     _ => false,
                 });
 
@@ -196,18 +235,40 @@ fn consume_generic_params(tokens: &mut TokenIter) -> Option<GenericParams> {
             }
             TokenTree::Punct(punct) if punct.as_char() == ',' => None,
             TokenTree::Punct(punct) if punct.as_char() == '>' => None,
+            TokenTree::Punct(punct) if punct.as_char() == '=' => None,
             token => {
                 panic!("cannot parse generic params: unexpected token {:?}", token)
             }
         };
 
-        let period = consume_period(tokens);
+        let default = match tokens.peek().unwrap() {
+            TokenTree::Punct(punct) if punct.as_char() == '=' => {
+                let eq = punct.clone();
+                // consume '='
+                tokens.next();
+
+                let default_tokens = consume_stuff_until(tokens, |token| match token {
+                    TokenTree::Punct(punct) if punct.as_char() == ',' => true,
+                    _ => false,
+                });
+
+                Some(GenericDefault {
+                    _eq: eq,
+                    tokens: default_tokens,
+                })
+            }
+            _ => None,
+        };
+
+        let period = consume_separator::<Comma>(tokens);
 
         generic_params.push(
             GenericParam {
+                attributes,
                 _prefix: prefix,
                 name,
                 bound,
+                default,
             },
             period,
         );
@@ -223,6 +284,159 @@ fn consume_generic_params(tokens: &mut TokenIter) -> Option<GenericParams> {
     })
 }
 
+fn consume_lifetime(tokens: &mut TokenIter) -> Option<Lifetime> {
+    let apostrophe = match tokens.peek() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '\'' => punct.clone(),
+        _ => return None,
+    };
+    tokens.next();
+
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(ident)) => ident,
+        token => panic!(
+            "cannot parse lifetime: expected identifier after '\'', found token {:?}",
+            token
+        ),
+    };
+
+    Some(Lifetime {
+        _apostrophe: apostrophe,
+        name,
+    })
+}
+
+fn consume_for_binder(tokens: &mut TokenIter) -> Option<ForBinder> {
+    let for_token = match tokens.peek() {
+        Some(TokenTree::Ident(ident)) if ident == "for" => ident.clone(),
+        _ => return None,
+    };
+    tokens.next();
+
+    let params = consume_generic_params(tokens)
+        .expect("cannot parse for binder: expected '<' after 'for' token");
+
+    Some(ForBinder {
+        _for: for_token,
+        params,
+    })
+}
+
+fn is_bounds_end(token: &TokenTree) -> bool {
+    match token {
+        TokenTree::Punct(punct) if punct.as_char() == ',' => true,
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => true,
+        TokenTree::Punct(punct) if punct.as_char() == ';' => true,
+        _ => false,
+    }
+}
+
+// Consumes a '+'-separated list of bounds, until
+// a ',', a ';', a '{}' group or the end of the stream
+pub(crate) fn consume_ty_bounds(tokens: &mut TokenIter) -> Punctuated<TyBound, Plus> {
+    let mut bounds = Punctuated::new();
+    loop {
+        match tokens.peek() {
+            None => break,
+            Some(token) if is_bounds_end(token) => break,
+            _ => (),
+        };
+
+        let bound = if let Some(lifetime) = consume_lifetime(tokens) {
+            TyBound::Lifetime(lifetime)
+        } else {
+            let question = match tokens.peek() {
+                Some(TokenTree::Punct(punct)) if punct.as_char() == '?' => {
+                    let punct = punct.clone();
+                    tokens.next();
+                    Some(punct)
+                }
+                _ => None,
+            };
+            let for_binder = consume_for_binder(tokens);
+            let path_tokens = consume_stuff_until(tokens, |token| match token {
+                TokenTree::Punct(punct) if Plus::is(punct) => true,
+                token => is_bounds_end(token),
+            });
+
+            if path_tokens.is_empty() {
+                panic!(
+                    "cannot parse bound: expected type, found token {:?}",
+                    tokens.peek()
+                );
+            }
+
+            TyBound::Trait(TraitBound {
+                _question: question,
+                for_binder,
+                path: TyExpr {
+                    tokens: path_tokens,
+                },
+            })
+        };
+
+        let plus = consume_separator::<Plus>(tokens);
+        let is_last = plus.is_none();
+        bounds.push(bound, plus);
+        if is_last {
+            break;
+        }
+    }
+
+    bounds
+}
+
+pub(crate) fn consume_where_clause_item(tokens: &mut TokenIter) -> WhereClauseItem {
+    if let Some(lifetime) = consume_lifetime(tokens) {
+        let colon = match tokens.next() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => punct,
+            token => panic!(
+                "cannot parse where clause: expected colon, found token {:?}",
+                token
+            ),
+        };
+
+        let mut bounds = Punctuated::new();
+        while let Some(bound) = consume_lifetime(tokens) {
+            let plus = consume_separator::<Plus>(tokens);
+            let is_last = plus.is_none();
+            bounds.push(bound, plus);
+            if is_last {
+                break;
+            }
+        }
+
+        return WhereClauseItem::Lifetime(LifetimePredicate {
+            lifetime,
+            _colon: colon,
+            bounds,
+        });
+    }
+
+    let for_binder = consume_for_binder(tokens);
+
+    let left_side = consume_stuff_until(tokens, |token| match token {
+        TokenTree::Punct(punct) if punct.as_char() == ':' => true,
+        _ => false,
+    });
+
+    let colon = match tokens.next() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => punct,
+        token => panic!(
+            "cannot parse where clause: expected colon, found token {:?}",
+            token
+        ),
+    };
+
+    let bounds = consume_ty_bounds(tokens);
+
+    WhereClauseItem::Ty(TyPredicate {
+        for_binder,
+        left_side: TyExpr { tokens: left_side },
+        _colon: colon,
+        bounds,
+    })
+}
+
 fn consume_where_clause(tokens: &mut TokenIter) -> Option<WhereClause> {
     let where_token: Ident;
     match tokens.peek() {
@@ -244,37 +458,10 @@ fn consume_where_clause(tokens: &mut TokenIter) -> Option<WhereClause> {
             _ => (),
         };
 
-        let left_side = consume_stuff_until(tokens, |token| match token {
-            TokenTree::Punct(punct) if punct.as_char() == ':' => true,
-            _ => false,
-        });
-
-        let colon = match tokens.next().unwrap() {
-            TokenTree::Punct(punct) if punct.as_char() == ':' => punct.clone(),
-            token => panic!(
-                "cannot parse where clause: expected colon, found token {:?}",
-                token
-            ),
-        };
-        let bound_tokens = consume_stuff_until(tokens, |token| match token {
-            TokenTree::Punct(punct) if punct.as_char() == ',' => true,
-            TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => true,
-            TokenTree::Punct(punct) if punct.as_char() == ';' => true,
-            _ => false,
-        });
-
-        let period = consume_period(tokens);
+        let item = consume_where_clause_item(tokens);
+        let period = consume_separator::<Comma>(tokens);
 
-        items.push(
-            WhereClauseItem {
-                left_side,
-                bound: GenericBound {
-                    _colon: colon,
-                    tokens: bound_tokens,
-                },
-            },
-            period,
-        );
+        items.push(item, period);
     }
 
     Some(WhereClause {
@@ -289,7 +476,7 @@ fn consume_field_type(tokens: &mut TokenIter) -> Vec<TokenTree> {
         _ => false,
     });
 
-    if field_type_tokens.is_empty() && consume_period(tokens).is_some() {
+    if field_type_tokens.is_empty() && consume_separator::<Comma>(tokens).is_some() {
         panic!("cannot parse type: unexpected token ','");
     } else if field_type_tokens.is_empty() {
         panic!("cannot parse type: expected tokens, found end-of-stream");
@@ -304,10 +491,34 @@ fn consume_enum_discriminant(tokens: &mut TokenIter) -> Option<EnumDiscriminant>
         _ => return None,
     };
 
-    let enum_discriminant_tokens = consume_stuff_until(tokens, |token| match token {
-        TokenTree::Punct(punct) if punct.as_char() == ',' => true,
-        _ => false,
-    });
+    let mut enum_discriminant_tokens = vec![tokens.next().unwrap()];
+
+    // Unlike in types, `<` and `>` are usually operators in expressions, so
+    // we only count the ones opening a turbofish or a qualified path.
+    let mut bracket_count = 0;
+    let mut prev_token_opens_generics = true;
+    loop {
+        let opens_generics = match tokens.peek() {
+            None => break,
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ',' && bracket_count == 0 => break,
+            Some(TokenTree::Punct(punct))
+                if punct.as_char() == '<' && prev_token_opens_generics =>
+            {
+                bracket_count += 1;
+                false
+            }
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '>' && bracket_count > 0 => {
+                bracket_count -= 1;
+                false
+            }
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => {
+                punct.spacing() == Spacing::Alone
+            }
+            _ => false,
+        };
+        prev_token_opens_generics = opens_generics;
+        enum_discriminant_tokens.push(tokens.next().unwrap());
+    }
 
     Some(EnumDiscriminant {
         tokens: enum_discriminant_tokens,
@@ -328,7 +539,7 @@ fn parse_tuple_fields(token_group: Group) -> TupleStructFields {
 
         let ty_tokens = consume_field_type(&mut tokens);
 
-        let period = consume_period(&mut tokens);
+        let period = consume_separator::<Comma>(&mut tokens);
 
         fields.push(
             TupleField {
@@ -369,7 +580,7 @@ fn parse_named_fields(token_group: Group) -> NamedStructFields {
         };
 
         let ty_tokens = consume_field_type(&mut tokens);
-        let period = consume_period(&mut tokens);
+        let period = consume_separator::<Comma>(&mut tokens);
 
         fields.push(
             NamedField {
@@ -424,7 +635,7 @@ fn parse_enum_variants(tokens: TokenStream) -> Punctuated<EnumVariant> {
 
         let enum_discriminant = consume_enum_discriminant(&mut tokens);
 
-        let period = consume_period(&mut tokens);
+        let period = consume_separator::<Comma>(&mut tokens);
 
         variants.push(
             EnumVariant {
@@ -538,7 +749,7 @@ fn parse_fn_params(tokens: TokenStream) -> Punctuated<FunctionParameter> {
         };
 
         let ty_tokens = consume_field_type(&mut tokens);
-        let period = consume_period(&mut tokens);
+        let period = consume_separator::<Comma>(&mut tokens);
 
         fields.push(
             FunctionParameter {
@@ -2,45 +2,223 @@
 
 use proc_macro2::{Punct, Spacing, TokenStream};
 use quote::{ToTokens, TokenStreamExt as _};
+use std::marker::PhantomData;
+
+type Iter<'a, T> = std::iter::Map<std::slice::Iter<'a, (T, Punct)>, fn(&(T, Punct)) -> &T>;
+type IterMut<'a, T> =
+    std::iter::Map<std::slice::IterMut<'a, (T, Punct)>, fn(&mut (T, Punct)) -> &mut T>;
+type IntoIter<T> = std::iter::Map<std::vec::IntoIter<(T, Punct)>, fn((T, Punct)) -> T>;
+
+/// The token separating the elements of a [`Punctuated`] sequence.
+pub trait Separator {
+    /// The character of the separator token.
+    const CHAR: char;
+
+    /// Returns a new separator token.
+    fn punct() -> Punct {
+        Punct::new(Self::CHAR, Spacing::Alone)
+    }
+
+    /// Returns true if `punct` is this separator.
+    fn is(punct: &Punct) -> bool {
+        punct.as_char() == Self::CHAR
+    }
+}
+
+/// The `,` separator, used by most lists: fields, variants, parameters, etc.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Comma;
+
+/// The `+` separator, used by lists of bounds, eg `Clone + Send + 'a`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Plus;
+
+/// The `|` separator, used by or-patterns and closure parameters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Or;
+
+/// The `;` separator, used by statements and macro rules.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Semi;
+
+impl Separator for Comma {
+    const CHAR: char = ',';
+}
+
+impl Separator for Plus {
+    const CHAR: char = '+';
+}
+
+impl Separator for Or {
+    const CHAR: char = '|';
+}
+
+impl Separator for Semi {
+    const CHAR: char = ';';
+}
 
 /// Inspired by syn's `Punctuated` type.
-#[derive(Clone)]
-pub struct Punctuated<T> {
+///
+/// `P` is the separator between elements, eg [`Comma`] or [`Plus`].
+pub struct Punctuated<T, P: Separator = Comma> {
     pub inner: Vec<(T, Punct)>,
     pub skip_last: bool,
+    separator: PhantomData<P>,
 }
 
-impl<T> Punctuated<T> {
+impl<T, P: Separator> Punctuated<T, P> {
     pub fn new() -> Self {
         Punctuated {
             inner: Vec::new(),
             skip_last: false,
+            separator: PhantomData,
         }
     }
 
+    /// Appends an element, followed by `period`.
+    ///
+    /// If `period` is None, the sequence has no trailing separator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` isn't a `P` separator.
     pub fn push(&mut self, value: T, period: Option<Punct>) {
         self.skip_last = period.is_none();
-        let period = period.unwrap_or(Punct::new(',', Spacing::Alone));
+        let period = Self::check_period(period);
         self.inner.push((value, period))
     }
 
     /// Inserts an element at position `index`.
     ///
+    /// If `period` is None, the element is followed by a default separator,
+    /// unless it's inserted at the end.
+    ///
     /// # Panics
     ///
     /// Panics if `index` is greater than the number of elements previously in
-    /// this punctuated sequence.
+    /// this punctuated sequence, or if `period` isn't a `P` separator.
     pub fn insert(&mut self, index: usize, value: T, period: Option<Punct>) {
         assert!(index <= self.len());
 
         if index == self.len() {
             self.push(value, period);
         } else {
-            self.inner
-                .insert(index, (value, Punct::new(',', Spacing::Alone)));
+            let period = Self::check_period(period);
+            self.inner.insert(index, (value, period));
         }
     }
 
+    fn check_period(period: Option<Punct>) -> Punct {
+        match period {
+            Some(period) => {
+                assert!(
+                    P::is(&period),
+                    "cannot use '{}' as a separator in a '{}'-separated list",
+                    period.as_char(),
+                    P::CHAR
+                );
+                period
+            }
+            None => P::punct(),
+        }
+    }
+
+    /// Removes and returns the element at position `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T {
+        self.inner.remove(index).0
+    }
+
+    /// Removes the last element and returns it, or None if the sequence is empty.
+    ///
+    /// Whether the sequence has a trailing separator is unchanged.
+    pub fn pop(&mut self) -> Option<T> {
+        self.inner.pop().map(|(item, _punct)| item)
+    }
+
+    pub fn clear(&mut self) {
+        self.inner.clear();
+        self.skip_last = false;
+    }
+
+    /// Keeps only the elements for which `f` returns true.
+    pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
+        self.inner.retain(|(item, _punct)| f(item));
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.inner.get(index).map(|(item, _punct)| item)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.inner.get_mut(index).map(|(item, _punct)| item)
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.inner.first().map(|(item, _punct)| item)
+    }
+
+    pub fn first_mut(&mut self) -> Option<&mut T> {
+        self.inner.first_mut().map(|(item, _punct)| item)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.inner.last().map(|(item, _punct)| item)
+    }
+
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        self.inner.last_mut().map(|(item, _punct)| item)
+    }
+
+    /// Returns an iterator over the elements, without their separators.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.inner.iter().map(|(item, _punct)| item)
+    }
+
+    /// Returns an iterator over mutable references to the elements.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.inner.iter_mut().map(|(item, _punct)| item)
+    }
+
+    /// Returns an iterator over the elements and the separators following them.
+    ///
+    /// The separator of the last element is None if there is no trailing separator.
+    pub fn pairs(&self) -> impl Iterator<Item = (&T, Option<&Punct>)> {
+        let len = self.inner.len();
+        let skip_last = self.skip_last;
+        self.inner
+            .iter()
+            .enumerate()
+            .map(move |(i, (item, punct))| {
+                let punct = if skip_last && i + 1 == len {
+                    None
+                } else {
+                    Some(punct)
+                };
+                (item, punct)
+            })
+    }
+
+    /// Same as [`Punctuated::pairs`], with mutable references.
+    pub fn pairs_mut(&mut self) -> impl Iterator<Item = (&mut T, Option<&mut Punct>)> {
+        let len = self.inner.len();
+        let skip_last = self.skip_last;
+        self.inner
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, (item, punct))| {
+                let punct = if skip_last && i + 1 == len {
+                    None
+                } else {
+                    Some(punct)
+                };
+                (item, punct)
+            })
+    }
+
     pub fn items(&self) -> impl Iterator<Item = &T> {
         self.inner.iter().map(|(item, _punct)| item)
     }
@@ -58,17 +236,31 @@ impl<T> Punctuated<T> {
     pub fn len(&self) -> usize {
         self.inner.len()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
 }
 
 // --- Trait impls ---
 
-impl<T> Default for Punctuated<T> {
+impl<T, P: Separator> Default for Punctuated<T, P> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<T: std::fmt::Debug> std::fmt::Debug for Punctuated<T> {
+impl<T: Clone, P: Separator> Clone for Punctuated<T, P> {
+    fn clone(&self) -> Self {
+        Punctuated {
+            inner: self.inner.clone(),
+            skip_last: self.skip_last,
+            separator: PhantomData,
+        }
+    }
+}
+
+impl<T: std::fmt::Debug, P: Separator> std::fmt::Debug for Punctuated<T, P> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut list = f.debug_list();
         for (item, _) in &self.inner {
@@ -78,7 +270,7 @@ impl<T: std::fmt::Debug> std::fmt::Debug for Punctuated<T> {
     }
 }
 
-impl<T: ToTokens> ToTokens for Punctuated<T> {
+impl<T: ToTokens, P: Separator> ToTokens for Punctuated<T, P> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         if self.inner.is_empty() {
             return;
@@ -95,10 +287,68 @@ impl<T: ToTokens> ToTokens for Punctuated<T> {
     }
 }
 
-impl<T> std::ops::Deref for Punctuated<T> {
+impl<T, P: Separator> std::ops::Deref for Punctuated<T, P> {
     type Target = [(T, Punct)];
 
     fn deref(&self) -> &Self::Target {
         &self.inner
     }
 }
+
+impl<T, P: Separator> std::ops::Index<usize> for Punctuated<T, P> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.inner[index].0
+    }
+}
+
+impl<T, P: Separator> std::ops::IndexMut<usize> for Punctuated<T, P> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.inner[index].0
+    }
+}
+
+impl<T, P: Separator> IntoIterator for Punctuated<T, P> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter().map(|(item, _punct)| item)
+    }
+}
+
+impl<'a, T, P: Separator> IntoIterator for &'a Punctuated<T, P> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, P: Separator> IntoIterator for &'a mut Punctuated<T, P> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, P: Separator> FromIterator<T> for Punctuated<T, P> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut punctuated = Punctuated::new();
+        punctuated.extend(iter);
+        punctuated
+    }
+}
+
+/// Elements are added without a trailing separator.
+impl<T, P: Separator> Extend<T> for Punctuated<T, P> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item, None);
+        }
+    }
+}
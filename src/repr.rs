@@ -0,0 +1,337 @@
+use crate::error::Error;
+use crate::spans::join_spans;
+use crate::types::{Attribute, Enum, Repr, ReprInt, ReprKind, Struct, StructFields, TyExpr, Union};
+use crate::Spanned;
+use proc_macro2::{Delimiter, Ident, Span, TokenTree};
+
+impl Struct {
+    /// Returns the layout given by the struct's `#[repr(...)]` attributes.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, Repr, ReprKind};
+    /// # use quote::quote;
+    /// let struct_type = parse_declaration(quote!(
+    ///     #[repr(C)]
+    ///     #[repr(align(8))]
+    ///     struct Hello(u32);
+    /// ));
+    /// let repr = struct_type.as_struct().unwrap().repr().unwrap();
+    /// assert_eq!(repr.kind, ReprKind::C);
+    /// assert_eq!(repr.align, Some(8));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a hint isn't recognized, if hints conflict (eg
+    /// `packed` and `align`), if the struct has an integer repr, or if a
+    /// `transparent` struct has more than one field which doesn't look zero-sized.
+    pub fn repr(&self) -> Result<Repr, Error> {
+        let (repr, spans) = parse_repr(&self.attributes)?;
+        if let Some(span) = spans.int {
+            return Err(Error::new(
+                span,
+                "integer representation is only allowed on enums",
+            ));
+        }
+        if let Some(span) = spans.transparent {
+            check_transparent_fields(span, &self.fields)?;
+        }
+        Ok(repr)
+    }
+}
+
+impl Enum {
+    /// Returns the layout given by the enum's `#[repr(...)]` attributes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a hint isn't recognized, if hints conflict (eg two
+    /// different integer types), if the enum is `packed`, or if a `transparent`
+    /// enum doesn't have exactly one variant with at most one non-zero-sized field.
+    pub fn repr(&self) -> Result<Repr, Error> {
+        let (repr, spans) = parse_repr(&self.attributes)?;
+        if let Some(span) = spans.packed {
+            return Err(Error::new(span, "`packed` isn't allowed on enums"));
+        }
+        if let Some(span) = spans.transparent {
+            let mut variants = self.variants.items();
+            match (variants.next(), variants.next()) {
+                (Some(variant), None) => check_transparent_fields(span, &variant.contents)?,
+                _ => {
+                    return Err(Error::new(
+                        span,
+                        "transparent enum needs exactly one variant",
+                    ))
+                }
+            }
+        }
+        Ok(repr)
+    }
+}
+
+impl Union {
+    /// Returns the layout given by the union's `#[repr(...)]` attributes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a hint isn't recognized, if hints conflict (eg
+    /// `packed` and `align`), if the union has an integer repr, or if a
+    /// `transparent` union has more than one field which doesn't look zero-sized.
+    pub fn repr(&self) -> Result<Repr, Error> {
+        let (repr, spans) = parse_repr(&self.attributes)?;
+        if let Some(span) = spans.int {
+            return Err(Error::new(
+                span,
+                "integer representation is only allowed on enums",
+            ));
+        }
+        if let Some(span) = spans.transparent {
+            let tys = self.fields.fields.items().map(|field| &field.ty);
+            check_transparent_tys(span, tys)?;
+        }
+        Ok(repr)
+    }
+}
+
+impl Repr {
+    /// Returns true for `#[repr(C)]`.
+    pub fn is_c(&self) -> bool {
+        self.kind == ReprKind::C
+    }
+
+    /// Returns true for `#[repr(transparent)]`.
+    pub fn is_transparent(&self) -> bool {
+        self.kind == ReprKind::Transparent
+    }
+}
+
+impl ReprInt {
+    /// Returns the name of the type, eg `"u8"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReprInt::I8 => "i8",
+            ReprInt::I16 => "i16",
+            ReprInt::I32 => "i32",
+            ReprInt::I64 => "i64",
+            ReprInt::I128 => "i128",
+            ReprInt::Isize => "isize",
+            ReprInt::U8 => "u8",
+            ReprInt::U16 => "u16",
+            ReprInt::U32 => "u32",
+            ReprInt::U64 => "u64",
+            ReprInt::U128 => "u128",
+            ReprInt::Usize => "usize",
+        }
+    }
+
+    /// Returns true for signed types.
+    pub fn is_signed(&self) -> bool {
+        matches!(
+            self,
+            ReprInt::I8
+                | ReprInt::I16
+                | ReprInt::I32
+                | ReprInt::I64
+                | ReprInt::I128
+                | ReprInt::Isize
+        )
+    }
+
+    pub(crate) fn from_ident(ident: &Ident) -> Option<Self> {
+        let repr_int = match ident.to_string().as_str() {
+            "i8" => ReprInt::I8,
+            "i16" => ReprInt::I16,
+            "i32" => ReprInt::I32,
+            "i64" => ReprInt::I64,
+            "i128" => ReprInt::I128,
+            "isize" => ReprInt::Isize,
+            "u8" => ReprInt::U8,
+            "u16" => ReprInt::U16,
+            "u32" => ReprInt::U32,
+            "u64" => ReprInt::U64,
+            "u128" => ReprInt::U128,
+            "usize" => ReprInt::Usize,
+            _ => return None,
+        };
+        Some(repr_int)
+    }
+}
+
+// Where each hint was found, for error messages.
+#[derive(Default)]
+struct ReprSpans {
+    kind: Option<Span>,
+    transparent: Option<Span>,
+    packed: Option<Span>,
+    align: Option<Span>,
+    int: Option<Span>,
+}
+
+fn parse_repr(attributes: &[Attribute]) -> Result<(Repr, ReprSpans), Error> {
+    let mut repr = Repr::default();
+    let mut spans = ReprSpans::default();
+
+    for attribute in attributes {
+        if !attribute.has_path("repr") {
+            continue;
+        }
+        let hints = match attribute.child_tokens.as_slice() {
+            [_repr, TokenTree::Group(group)] if group.delimiter() == Delimiter::Parenthesis => {
+                group.stream().into_iter().collect::<Vec<_>>()
+            }
+            _ => return Err(Error::new(attribute.span(), "expected `#[repr(...)]`")),
+        };
+
+        for hint in
+            hints.split(|token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ','))
+        {
+            let (name, arg) = match hint {
+                [] => continue,
+                [TokenTree::Ident(name)] => (name, None),
+                [TokenTree::Ident(name), TokenTree::Group(arg)]
+                    if arg.delimiter() == Delimiter::Parenthesis =>
+                {
+                    (name, Some(arg))
+                }
+                _ => {
+                    let span = join_spans(hint.iter().cloned());
+                    return Err(Error::new(span, "unrecognized representation hint"));
+                }
+            };
+            let span = name.span();
+
+            let arg = match arg {
+                Some(arg) => {
+                    let value = parse_alignment(arg.stream().into_iter().collect())
+                        .ok_or_else(|| Error::new(arg.span(), "expected a power of two"))?;
+                    Some(value)
+                }
+                None => None,
+            };
+
+            match (name.to_string().as_str(), arg) {
+                ("C", None) => set_kind(&mut repr, &mut spans, ReprKind::C, span)?,
+                ("Rust", None) => set_kind(&mut repr, &mut spans, ReprKind::Rust, span)?,
+                ("transparent", None) => {
+                    set_kind(&mut repr, &mut spans, ReprKind::Transparent, span)?;
+                    spans.transparent = Some(span);
+                }
+                ("packed", arg) => {
+                    let value = arg.unwrap_or(1);
+                    if matches!(repr.packed, Some(packed) if packed != value) {
+                        return Err(Error::new(
+                            span,
+                            "conflicting `packed` representation hints",
+                        ));
+                    }
+                    repr.packed = Some(value);
+                    spans.packed = Some(span);
+                }
+                ("align", Some(value)) => {
+                    if matches!(repr.align, Some(align) if align != value) {
+                        return Err(Error::new(span, "conflicting `align` representation hints"));
+                    }
+                    repr.align = Some(value);
+                    spans.align = Some(span);
+                }
+                (_, None) => {
+                    let repr_int = ReprInt::from_ident(name)
+                        .ok_or_else(|| Error::new(span, "unrecognized representation hint"))?;
+                    if matches!(repr.int, Some(int) if int != repr_int) {
+                        return Err(Error::new(span, "conflicting representation hints"));
+                    }
+                    repr.int = Some(repr_int);
+                    spans.int = Some(span);
+                }
+                _ => return Err(Error::new(span, "unrecognized representation hint")),
+            }
+        }
+    }
+
+    if let (Some(_), Some(span)) = (spans.packed, spans.align) {
+        return Err(Error::new(
+            span,
+            "`packed` and `align` can't be used together",
+        ));
+    }
+    if spans.transparent.is_some() {
+        if let Some(span) = spans.packed.or(spans.align).or(spans.int) {
+            return Err(Error::new(
+                span,
+                "transparent types can't have other representation hints",
+            ));
+        }
+    }
+
+    Ok((repr, spans))
+}
+
+fn set_kind(
+    repr: &mut Repr,
+    spans: &mut ReprSpans,
+    kind: ReprKind,
+    span: Span,
+) -> Result<(), Error> {
+    if spans.kind.is_some() && repr.kind != kind {
+        return Err(Error::new(span, "conflicting representation hints"));
+    }
+    repr.kind = kind;
+    spans.kind = Some(span);
+    Ok(())
+}
+
+fn parse_alignment(tokens: Vec<TokenTree>) -> Option<u32> {
+    let literal = match tokens.as_slice() {
+        [TokenTree::Literal(literal)] => literal.to_string(),
+        _ => return None,
+    };
+    let value: u32 = literal.parse().ok()?;
+    if value.is_power_of_two() {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+fn check_transparent_fields(span: Span, fields: &StructFields) -> Result<(), Error> {
+    check_transparent_tys(span, fields.iter().map(|field| field.ty))
+}
+
+fn check_transparent_tys<'a>(
+    span: Span,
+    tys: impl Iterator<Item = &'a TyExpr>,
+) -> Result<(), Error> {
+    let non_zst_count = tys.filter(|ty| !looks_zero_sized(ty)).count();
+    if non_zst_count > 1 {
+        return Err(Error::new(
+            span,
+            format!(
+                "transparent type needs at most one non-zero-sized field, but has {}",
+                non_zst_count
+            ),
+        ));
+    }
+    Ok(())
+}
+
+// We can't know the size of a type, so we only recognize `PhantomData<...>`,
+// `()` and `[T; 0]`.
+fn looks_zero_sized(ty: &TyExpr) -> bool {
+    if ty.is_phantom_data() {
+        return true;
+    }
+    match ty.tokens.as_slice() {
+        [TokenTree::Group(group)] if group.delimiter() == Delimiter::Parenthesis => {
+            group.stream().is_empty()
+        }
+        [TokenTree::Group(group)] if group.delimiter() == Delimiter::Bracket => {
+            let tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+            matches!(
+                tokens.as_slice(),
+                [.., TokenTree::Punct(semicolon), TokenTree::Literal(len)]
+                    if semicolon.as_char() == ';' && len.to_string() == "0"
+            )
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,328 @@
+use crate::punctuated::{Punctuated, Separator};
+use crate::types::{
+    Attribute, Declaration, Enum, EnumDiscriminant, EnumVariant, ForBinder, Function,
+    FunctionParameter, FunctionQualifiers, GenericBound, GenericDefault, GenericParam,
+    GenericParams, Lifetime, LifetimePredicate, NamedField, NamedStructFields, Struct,
+    StructFields, TraitBound, TupleField, TupleStructFields, TyBound, TyExpr, TyPredicate, Union,
+    VisMarker, WhereClause, WhereClauseItem,
+};
+use proc_macro2::{Group, Ident, Literal, Punct, Span, TokenStream, TokenTree};
+use quote::ToTokens;
+
+/// A node whose span can be computed from its tokens.
+///
+/// This is implemented for every venial node, and for every other type which
+/// implements [`ToTokens`].
+pub trait Spanned {
+    /// Returns a span covering the whole node.
+    ///
+    /// Joining spans is only supported on nightly compilers. On stable, this
+    /// returns the span of the node's first token instead. For empty nodes,
+    /// this returns [`Span::call_site`].
+    fn span(&self) -> Span;
+}
+
+impl<T: ToTokens + ?Sized> Spanned for T {
+    fn span(&self) -> Span {
+        join_spans(self.to_token_stream())
+    }
+}
+
+pub(crate) fn join_spans(tokens: impl IntoIterator<Item = TokenTree>) -> Span {
+    let mut tokens = tokens.into_iter();
+    let first = match tokens.next() {
+        Some(token) => token.span(),
+        None => return Span::call_site(),
+    };
+    let last = tokens.last().map_or(first, |token| token.span());
+    first.join(last).unwrap_or(first)
+}
+
+// Implemented by every node and token type, to remap the spans of all the
+// tokens it holds, including the ones inside groups.
+trait MapSpans {
+    fn map_spans_with(&mut self, f: &mut dyn FnMut(Span) -> Span);
+}
+
+macro_rules! impl_span_methods {
+    ($($ty:ty),*) => {
+        $(
+            impl $ty {
+                /// Sets the span of every token, including delimiters and the
+                /// tokens inside groups.
+                pub fn set_span(&mut self, span: Span) {
+                    self.map_spans_with(&mut |_| span);
+                }
+
+                /// Replaces the span of every token with the span returned by `f`.
+                ///
+                /// Like [`set_span`](Self::set_span), this includes delimiters
+                /// and the tokens inside groups.
+                pub fn map_spans(&mut self, mut f: impl FnMut(Span) -> Span) {
+                    self.map_spans_with(&mut f);
+                }
+            }
+        )*
+    };
+}
+
+impl_span_methods!(
+    Declaration,
+    Struct,
+    Enum,
+    EnumVariant,
+    Union,
+    Function,
+    FunctionParameter,
+    StructFields,
+    NamedField,
+    TupleField,
+    Attribute,
+    VisMarker,
+    GenericParams,
+    GenericParam,
+    WhereClause,
+    WhereClauseItem,
+    Lifetime,
+    TyBound,
+    TyExpr
+);
+
+// Implements `MapSpans` for a struct by mapping each of the listed fields.
+//
+// The groups listed after `delimiters` are only used for the span of their
+// delimiters, as their contents are printed from other fields.
+macro_rules! map_fields {
+    ($ty:ty { $($field:ident),* } $(delimiters { $($group:ident),* })?) => {
+        impl MapSpans for $ty {
+            fn map_spans_with(&mut self, f: &mut dyn FnMut(Span) -> Span) {
+                $(self.$field.map_spans_with(f);)*
+                $($(self.$group.set_span(f(self.$group.span()));)*)?
+            }
+        }
+    };
+}
+
+map_fields!(Struct {
+    attributes,
+    vis_marker,
+    _struct,
+    name,
+    generic_params,
+    where_clause,
+    fields,
+    _semicolon
+});
+map_fields!(TupleStructFields { fields } delimiters { tk_parens });
+map_fields!(NamedStructFields { fields } delimiters { tk_braces });
+map_fields!(Enum {
+    attributes,
+    vis_marker,
+    _enum,
+    name,
+    generic_params,
+    where_clause,
+    variants
+} delimiters { tk_braces });
+map_fields!(EnumVariant {
+    attributes,
+    vis_marker,
+    name,
+    contents,
+    discriminant
+});
+map_fields!(EnumDiscriminant { tokens });
+map_fields!(Union {
+    attributes,
+    vis_marker,
+    _union,
+    name,
+    generic_params,
+    where_clause,
+    fields
+} delimiters { tk_braces });
+map_fields!(Function {
+    attributes,
+    vis_marker,
+    qualifiers,
+    name,
+    generic_params,
+    params,
+    where_clause,
+    return_ty,
+    body
+});
+map_fields!(FunctionQualifiers {
+    tk_default,
+    tk_const,
+    tk_async,
+    tk_unsafe,
+    tk_extern,
+    extern_abi
+});
+map_fields!(FunctionParameter {
+    attributes,
+    name,
+    ty
+});
+map_fields!(TupleField {
+    attributes,
+    vis_marker,
+    ty
+});
+map_fields!(NamedField {
+    attributes,
+    vis_marker,
+    name,
+    _colon,
+    ty
+});
+map_fields!(Attribute {
+    _hashbang,
+    child_tokens
+} delimiters { _braces });
+map_fields!(VisMarker { _token1, _token2 });
+map_fields!(GenericParams {
+    _l_bracket,
+    params,
+    _r_bracket
+});
+map_fields!(GenericParam {
+    attributes,
+    _prefix,
+    name,
+    bound,
+    default
+});
+map_fields!(GenericBound { _colon, tokens });
+map_fields!(GenericDefault { _eq, tokens });
+map_fields!(WhereClause { _where, items });
+map_fields!(LifetimePredicate {
+    lifetime,
+    _colon,
+    bounds
+});
+map_fields!(TyPredicate {
+    for_binder,
+    left_side,
+    _colon,
+    bounds
+});
+map_fields!(ForBinder { _for, params });
+map_fields!(Lifetime { _apostrophe, name });
+map_fields!(TraitBound {
+    _question,
+    for_binder,
+    path
+});
+map_fields!(TyExpr { tokens });
+
+impl MapSpans for Declaration {
+    fn map_spans_with(&mut self, f: &mut dyn FnMut(Span) -> Span) {
+        match self {
+            Declaration::Struct(struct_decl) => struct_decl.map_spans_with(f),
+            Declaration::Enum(enum_decl) => enum_decl.map_spans_with(f),
+            Declaration::Union(union_decl) => union_decl.map_spans_with(f),
+            Declaration::Function(function_decl) => function_decl.map_spans_with(f),
+        }
+    }
+}
+
+impl MapSpans for StructFields {
+    fn map_spans_with(&mut self, f: &mut dyn FnMut(Span) -> Span) {
+        match self {
+            StructFields::Unit => (),
+            StructFields::Tuple(fields) => fields.map_spans_with(f),
+            StructFields::Named(fields) => fields.map_spans_with(f),
+        }
+    }
+}
+
+impl MapSpans for WhereClauseItem {
+    fn map_spans_with(&mut self, f: &mut dyn FnMut(Span) -> Span) {
+        match self {
+            WhereClauseItem::Lifetime(predicate) => predicate.map_spans_with(f),
+            WhereClauseItem::Ty(predicate) => predicate.map_spans_with(f),
+        }
+    }
+}
+
+impl MapSpans for TyBound {
+    fn map_spans_with(&mut self, f: &mut dyn FnMut(Span) -> Span) {
+        match self {
+            TyBound::Lifetime(lifetime) => lifetime.map_spans_with(f),
+            TyBound::Trait(trait_bound) => trait_bound.map_spans_with(f),
+        }
+    }
+}
+
+// --- Tokens and containers ---
+
+impl MapSpans for Ident {
+    fn map_spans_with(&mut self, f: &mut dyn FnMut(Span) -> Span) {
+        self.set_span(f(self.span()));
+    }
+}
+
+impl MapSpans for Punct {
+    fn map_spans_with(&mut self, f: &mut dyn FnMut(Span) -> Span) {
+        self.set_span(f(self.span()));
+    }
+}
+
+impl MapSpans for Literal {
+    fn map_spans_with(&mut self, f: &mut dyn FnMut(Span) -> Span) {
+        self.set_span(f(self.span()));
+    }
+}
+
+impl MapSpans for Group {
+    fn map_spans_with(&mut self, f: &mut dyn FnMut(Span) -> Span) {
+        let mut tokens: Vec<TokenTree> = self.stream().into_iter().collect();
+        tokens.map_spans_with(f);
+        let mut group = Group::new(
+            self.delimiter(),
+            tokens.into_iter().collect::<TokenStream>(),
+        );
+        group.set_span(f(self.span()));
+        *self = group;
+    }
+}
+
+impl MapSpans for TokenTree {
+    fn map_spans_with(&mut self, f: &mut dyn FnMut(Span) -> Span) {
+        match self {
+            TokenTree::Group(group) => group.map_spans_with(f),
+            TokenTree::Ident(ident) => ident.map_spans_with(f),
+            TokenTree::Punct(punct) => punct.map_spans_with(f),
+            TokenTree::Literal(literal) => literal.map_spans_with(f),
+        }
+    }
+}
+
+impl<T: MapSpans> MapSpans for Option<T> {
+    fn map_spans_with(&mut self, f: &mut dyn FnMut(Span) -> Span) {
+        if let Some(value) = self {
+            value.map_spans_with(f);
+        }
+    }
+}
+
+impl<T: MapSpans> MapSpans for Vec<T> {
+    fn map_spans_with(&mut self, f: &mut dyn FnMut(Span) -> Span) {
+        for value in self {
+            value.map_spans_with(f);
+        }
+    }
+}
+
+impl<T: MapSpans, P: Separator> MapSpans for Punctuated<T, P> {
+    fn map_spans_with(&mut self, f: &mut dyn FnMut(Span) -> Span) {
+        for (value, punct) in self.pairs_mut() {
+            value.map_spans_with(f);
+            if let Some(punct) = punct {
+                punct.map_spans_with(f);
+            }
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use crate::types::{
+    Declaration, EnumDiscriminant, GenericParam, GenericParams, Lifetime, StructFields, TyExpr,
+    WhereClause, WhereClauseItem,
+};
+use proc_macro2::{Delimiter, Group, Ident, Spacing, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
+
+// What to replace, and what to replace it with.
+enum Substitution<'a> {
+    Ty(&'a Ident, Vec<TokenTree>),
+    Lifetime(&'a Ident, Vec<TokenTree>),
+    Const(&'a Ident, Vec<TokenTree>),
+}
+
+impl Declaration {
+    /// Replaces the type parameter `name` with `ty` everywhere in the declaration,
+    /// and removes it from the declaration's generic params.
+    ///
+    /// The parameter is replaced in field types, discriminants, function parameters
+    /// and return types, the bounds and defaults of other parameters, and where
+    /// clause items. Function bodies are left untouched.
+    ///
+    /// Path segments with the same name aren't replaced, so `a::T` and
+    /// `<X as Trait>::T` stay the same. `T::Item` becomes `<ty>::Item`.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, TyExpr};
+    /// # use quote::{format_ident, quote};
+    /// let mut declaration = parse_declaration(quote!(
+    ///     struct Hello<T, U> {
+    ///         a: Vec<T>,
+    ///         b: U,
+    ///     }
+    /// ));
+    /// let ty = TyExpr { tokens: quote!(u32).into_iter().collect() };
+    /// declaration.substitute_generic(&format_ident!("T"), ty);
+    /// assert_eq!(
+    ///     quote!(#declaration).to_string(),
+    ///     quote!(struct Hello<U> { a: Vec<u32>, b: U, }).to_string(),
+    /// );
+    /// ```
+    pub fn substitute_generic(&mut self, name: &Ident, ty: TyExpr) {
+        self.substitute(&Substitution::Ty(name, ty.tokens));
+    }
+
+    /// Replaces the lifetime parameter `'name` with `lifetime` everywhere in the
+    /// declaration, and removes it from the declaration's generic params.
+    ///
+    /// See [`Declaration::substitute_generic`] for details.
+    pub fn substitute_lifetime(&mut self, name: &Ident, lifetime: Lifetime) {
+        let lifetime_tokens = lifetime.to_token_stream().into_iter().collect();
+        self.substitute(&Substitution::Lifetime(name, lifetime_tokens));
+    }
+
+    /// Replaces the const parameter `name` with `value` everywhere in the
+    /// declaration, and removes it from the declaration's generic params.
+    ///
+    /// Values other than a single literal or identifier are wrapped in braces,
+    /// eg `Foo<N>` becomes `Foo<{ 1 + 2 }>`.
+    ///
+    /// See [`Declaration::substitute_generic`] for details.
+    pub fn substitute_const(&mut self, name: &Ident, value: TokenStream) {
+        let value_tokens: Vec<TokenTree> = value.into_iter().collect();
+        let value_tokens = match value_tokens.as_slice() {
+            [TokenTree::Literal(_)] | [TokenTree::Ident(_)] => value_tokens,
+            _ => vec![Group::new(Delimiter::Brace, value_tokens.into_iter().collect()).into()],
+        };
+        self.substitute(&Substitution::Const(name, value_tokens));
+    }
+
+    fn substitute(&mut self, substitution: &Substitution) {
+        let (generic_params, where_clause) = match self {
+            Declaration::Struct(struct_decl) => {
+                substitution.apply_to_fields(&mut struct_decl.fields);
+                (
+                    &mut struct_decl.generic_params,
+                    &mut struct_decl.where_clause,
+                )
+            }
+            Declaration::Enum(enum_decl) => {
+                for (variant, _punct) in &mut enum_decl.variants.inner {
+                    substitution.apply_to_fields(&mut variant.contents);
+                    if let Some(discriminant) = &mut variant.discriminant {
+                        *discriminant = EnumDiscriminant {
+                            tokens: substitution.apply(&discriminant.tokens),
+                        };
+                    }
+                }
+                (&mut enum_decl.generic_params, &mut enum_decl.where_clause)
+            }
+            Declaration::Union(union_decl) => {
+                for (field, _punct) in &mut union_decl.fields.fields.inner {
+                    substitution.apply_to_ty(&mut field.ty);
+                }
+                (&mut union_decl.generic_params, &mut union_decl.where_clause)
+            }
+            Declaration::Function(function_decl) => {
+                for (param, _punct) in &mut function_decl.params.inner {
+                    substitution.apply_to_ty(&mut param.ty);
+                }
+                if let Some(return_ty) = &mut function_decl.return_ty {
+                    substitution.apply_to_ty(return_ty);
+                }
+                (
+                    &mut function_decl.generic_params,
+                    &mut function_decl.where_clause,
+                )
+            }
+        };
+
+        if let Some(params) = generic_params {
+            substitution.apply_to_generic_params(params);
+            if params.params.is_empty() {
+                *generic_params = None;
+            }
+        }
+        if let Some(where_clause) = where_clause {
+            substitution.apply_to_where_clause(where_clause);
+        }
+    }
+}
+
+impl Substitution<'_> {
+    fn name(&self) -> &Ident {
+        match self {
+            Substitution::Ty(name, _) => name,
+            Substitution::Lifetime(name, _) => name,
+            Substitution::Const(name, _) => name,
+        }
+    }
+
+    fn is_lifetime(&self) -> bool {
+        matches!(self, Substitution::Lifetime(..))
+    }
+
+    fn apply_to_fields(&self, fields: &mut StructFields) {
+        match fields {
+            StructFields::Unit => (),
+            StructFields::Tuple(tuple_fields) => {
+                for (field, _punct) in &mut tuple_fields.fields.inner {
+                    self.apply_to_ty(&mut field.ty);
+                }
+            }
+            StructFields::Named(named_fields) => {
+                for (field, _punct) in &mut named_fields.fields.inner {
+                    self.apply_to_ty(&mut field.ty);
+                }
+            }
+        }
+    }
+
+    fn apply_to_ty(&self, ty: &mut TyExpr) {
+        ty.tokens = self.apply(&ty.tokens);
+    }
+
+    fn apply_to_generic_params(&self, params: &mut GenericParams) {
+        let old_params = std::mem::take(&mut params.params);
+        let skip_last = old_params.skip_last;
+
+        for (mut param, punct) in old_params.inner {
+            if param.is_lifetime() == self.is_lifetime() && &param.name == self.name() {
+                continue;
+            }
+            self.apply_to_generic_param(&mut param);
+            params.params.push(param, Some(punct));
+        }
+        params.params.skip_last = skip_last;
+    }
+
+    fn apply_to_generic_param(&self, param: &mut GenericParam) {
+        if let Some(bound) = &mut param.bound {
+            bound.tokens = self.apply(&bound.tokens);
+        }
+        if let Some(default) = &mut param.default {
+            default.tokens = self.apply(&default.tokens);
+        }
+    }
+
+    fn apply_to_where_clause(&self, where_clause: &mut WhereClause) {
+        let old_items = std::mem::take(&mut where_clause.items);
+        let skip_last = old_items.skip_last;
+
+        for (item, punct) in old_items.inner {
+            let item_tokens: Vec<TokenTree> = item.to_token_stream().into_iter().collect();
+            let item_tokens = self.apply(&item_tokens);
+            let item = WhereClauseItem::parse(quote!(#(#item_tokens)*));
+            where_clause.items.push(item, Some(punct));
+        }
+        where_clause.items.skip_last = skip_last;
+    }
+
+    // Replaces occurrences of the substituted name, recursing into groups.
+    fn apply(&self, tokens: &[TokenTree]) -> Vec<TokenTree> {
+        let mut output_tokens = Vec::new();
+
+        for (i, token) in tokens.iter().enumerate() {
+            let prev_token = if i > 0 { tokens.get(i - 1) } else { None };
+            let next_token = tokens.get(i + 1);
+
+            match token {
+                TokenTree::Group(group) => {
+                    let group_tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+                    let group_tokens = self.apply(&group_tokens);
+                    let mut new_group =
+                        Group::new(group.delimiter(), group_tokens.into_iter().collect());
+                    new_group.set_span(group.span());
+                    output_tokens.push(new_group.into());
+                }
+                TokenTree::Ident(ident) if ident == self.name() => match self {
+                    Substitution::Lifetime(_, replacement) if is_punct(prev_token, '\'') => {
+                        // Replace the `'` token we already pushed
+                        output_tokens.pop();
+                        output_tokens.extend(replacement.iter().cloned());
+                    }
+                    Substitution::Ty(_, replacement) | Substitution::Const(_, replacement)
+                        if !is_punct(prev_token, '\'')
+                            && !is_punct(prev_token, ':')
+                            && !is_punct(prev_token, '.') =>
+                    {
+                        if replacement.len() > 1 && is_path_separator(next_token) {
+                            // `T::Item` becomes `<Foo<X>>::Item`
+                            output_tokens.push(punct('<', Spacing::Alone));
+                            output_tokens.extend(replacement.iter().cloned());
+                            output_tokens.push(punct('>', Spacing::Alone));
+                        } else {
+                            output_tokens.extend(replacement.iter().cloned());
+                        }
+                    }
+                    _ => output_tokens.push(token.clone()),
+                },
+                token => output_tokens.push(token.clone()),
+            }
+        }
+
+        output_tokens
+    }
+}
+
+fn is_punct(token: Option<&TokenTree>, c: char) -> bool {
+    matches!(token, Some(TokenTree::Punct(punct)) if punct.as_char() == c)
+}
+
+fn is_path_separator(token: Option<&TokenTree>) -> bool {
+    matches!(token, Some(TokenTree::Punct(punct)) if punct.as_char() == ':' && punct.spacing() == Spacing::Joint)
+}
+
+fn punct(c: char, spacing: Spacing) -> TokenTree {
+    proc_macro2::Punct::new(c, spacing).into()
+}
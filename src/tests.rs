@@ -1,8 +1,14 @@
-use crate::{parse_declaration, Declaration, GenericParam, Struct, WhereClauseItem};
+use crate::{
+    parse_declaration, Attribute, Binding, BindingMode, CfgOptions, CfgPredicate, Comma,
+    Declaration, Enum, EnumVariant, Function, FunctionParameter, GenericBound, GenericParam,
+    IdentExt, ImplTraitOptions, Lifetime, LintLevel, NameGen, NamedField, Or, Plus, Punctuated,
+    RenameRule, Semi, Separator, Struct, StructFields, TupleField, TyExpr, Union, VisMarker,
+    WhereClauseItem,
+};
 
 use insta::assert_debug_snapshot;
-use proc_macro2::TokenStream;
-use quote::quote;
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote};
 
 macro_rules! assert_quote_snapshot {
     ($item:expr) => {{
@@ -12,6 +18,17 @@ macro_rules! assert_quote_snapshot {
     }};
 }
 
+// Compiles the given items, and returns them as tokens.
+macro_rules! checked_quote {
+    ($($tokens:tt)*) => {{
+        #[allow(dead_code)]
+        const _: () = {
+            $($tokens)*
+        };
+        quote!($($tokens)*)
+    }};
+}
+
 fn parse_declaration_checked(tokens: TokenStream) -> Declaration {
     let initial_tokens = tokens.clone();
     let declaration = parse_declaration(tokens);
@@ -421,6 +438,45 @@ fn parse_enum_empty_where_clause() {
     assert_debug_snapshot!(enum_type);
 }
 
+#[test]
+fn parse_where_clause_predicates() {
+    let struct_type = parse_declaration_checked(quote!(
+        struct Hello<'a, 'b, T>
+        where
+            'a: 'b + 'static,
+            for<'de> T: Deserialize<'de> + ?Sized,
+            T::Item: Clone,
+            <T as Trait>::Out: 'a + for<'c> Fn(&'c A),
+        {
+            a: &'a T,
+        }
+    ));
+
+    let where_clause = match struct_type {
+        Declaration::Struct(struct_type) => struct_type.where_clause.unwrap(),
+        _ => unreachable!(),
+    };
+    let items: Vec<_> = where_clause.items.items().collect();
+    let lifetime_predicates: Vec<_> = items
+        .iter()
+        .filter_map(|item| match item {
+            WhereClauseItem::Lifetime(predicate) => Some(predicate),
+            _ => None,
+        })
+        .collect();
+    let ty_predicates: Vec<_> = items
+        .iter()
+        .filter_map(|item| match item {
+            WhereClauseItem::Ty(predicate) => Some(predicate),
+            _ => None,
+        })
+        .collect();
+
+    assert!(items[0].is_lifetime());
+    assert_debug_snapshot!(lifetime_predicates);
+    assert_debug_snapshot!(ty_predicates);
+}
+
 // ==============
 // GENERIC PARAMS
 // ==============
@@ -485,6 +541,17 @@ fn parse_enum_empty_generic_params() {
     assert_debug_snapshot!(enum_type);
 }
 
+#[test]
+fn parse_generic_params_defaults_and_attributes() {
+    let struct_type = parse_declaration_checked(quote!(
+        struct Hello<'a: 'static, #[my_attr] T: Iterator<Item = u8> = Foo, const N: usize = 3>(
+            &'a [T; N],
+        );
+    ));
+
+    assert_debug_snapshot!(struct_type);
+}
+
 // =================
 // ENUM DISCRIMINANT
 // =================
@@ -575,7 +642,7 @@ fn parse_macro_in_where_clause() {
 
 #[test]
 fn parse_fn() {
-    let func = parse_declaration(quote! {
+    let func = parse_declaration_checked(quote! {
         fn hello(a: i32, b: f32) -> String {}
     });
 
@@ -602,7 +669,7 @@ fn parse_generic_fn() {
 
 #[test]
 fn parse_where_fn() {
-    let func = parse_declaration(quote! {
+    let func = parse_declaration_checked(quote! {
         fn where_clause<T>() -> T
         where
             T: Debug
@@ -694,7 +761,7 @@ fn parse_extern_fn() {
 
 #[test]
 fn parse_all_kw_fn() {
-    let func = parse_declaration(quote! {
+    let func = parse_declaration_checked(quote! {
         pub default const async unsafe extern "C" fn all_kw(b: f32) {}
     });
 
@@ -703,7 +770,7 @@ fn parse_all_kw_fn() {
 
 #[test]
 fn parse_param_attr_fn() {
-    let func = parse_declaration(quote! {
+    let func = parse_declaration_checked(quote! {
         pub async fn visibility(#[my_attr] b: f32) {}
     });
 
@@ -723,7 +790,7 @@ fn parse_fn_body() {
 
 #[test]
 fn parse_fn_prototype() {
-    let func = parse_declaration(quote! {
+    let func = parse_declaration_checked(quote! {
         fn prototype(a: i32, b: f32) -> String;
     });
 
@@ -937,3 +1004,1251 @@ fn add_where_item() {
     assert_quote_snapshot!(basic_type);
     assert_quote_snapshot!(type_with_args);
 }
+
+#[test]
+fn split_for_impl() {
+    let basic_type = parse_declaration(quote!(
+        struct Hello {
+            a: A,
+            b: B,
+        }
+    ));
+    let generic_type = parse_declaration(quote!(
+        struct Hello<'a: 'static, #[my_attr] T: Clone = i32, const N: usize = 3>
+        where
+            T: Default,
+        {
+            a: &'a [T; N],
+        }
+    ));
+
+    let (impl_generics, ty_generics, where_clause) = basic_type.split_for_impl();
+    let basic_impl = quote!(impl #impl_generics MyTrait for Hello #ty_generics #where_clause {});
+    let (impl_generics, ty_generics, where_clause) = generic_type.split_for_impl();
+    let generic_impl = quote!(impl #impl_generics MyTrait for Hello #ty_generics #where_clause {});
+
+    assert_quote_snapshot!(basic_impl);
+    assert_quote_snapshot!(generic_impl);
+}
+
+#[test]
+fn hoist_and_inline_bounds() {
+    let mut struct_type = parse_struct_declaration(quote!(
+        struct Hello<'a: 'b, 'b, T: Clone, const N: usize>
+        where
+            T: Default,
+            T::Item: Clone,
+        {
+            a: &'a [T; N],
+        }
+    ));
+
+    let generic_params = struct_type.generic_params.as_mut().unwrap();
+    let where_clause = struct_type.where_clause.as_mut().unwrap();
+
+    generic_params.hoist_bounds(where_clause);
+    let hoisted_type = struct_type.clone();
+
+    let generic_params = struct_type.generic_params.as_mut().unwrap();
+    let where_clause = struct_type.where_clause.as_mut().unwrap();
+
+    generic_params.inline_bounds(where_clause);
+    let inlined_type = struct_type;
+
+    assert_quote_snapshot!(hoisted_type);
+    assert_quote_snapshot!(inlined_type);
+}
+
+#[test]
+fn merge_and_dedup_where_items() {
+    let struct_type = parse_struct_declaration(quote!(
+        struct Hello<'a, 'b, T, U>
+        where
+            T: Clone,
+            'a: 'b,
+            U: Default,
+            T: Default + Clone,
+            U: Default,
+            'a: 'b,
+        {
+            a: &'a T,
+            b: &'b U,
+        }
+    ));
+
+    let mut merged_where_clause = struct_type.where_clause.clone().unwrap();
+    merged_where_clause.merge_items();
+    let mut deduped_where_clause = struct_type.where_clause.clone().unwrap();
+    deduped_where_clause.dedup_items();
+    let mut normalized_where_clause = struct_type.where_clause.unwrap();
+    normalized_where_clause.merge_items();
+    normalized_where_clause.dedup_items();
+
+    assert_quote_snapshot!(merged_where_clause);
+    assert_quote_snapshot!(deduped_where_clause);
+    assert_quote_snapshot!(normalized_where_clause);
+}
+
+#[test]
+fn create_field_bound_where_clause() {
+    let struct_type = parse_struct_declaration(quote!(
+        struct Hello<'a, T: Iterator, U, V>
+        where
+            U: Default,
+        {
+            a: Box<T>,
+            b: T::Item,
+            c: PhantomData<V>,
+            d: &'a str,
+            #[my_attr]
+            e: U,
+            f: Box<T>,
+        }
+    ));
+    let enum_type = match parse_declaration(quote!(
+        enum Hello<T, U> {
+            A(T),
+            B { b: Vec<U>, c: u32 },
+            C,
+        }
+    )) {
+        Declaration::Enum(enum_type) => enum_type,
+        _ => unreachable!(),
+    };
+
+    let struct_where_clause =
+        struct_type.create_field_bound_where_clause(quote!(Clone), Some("my_attr"));
+    let enum_where_clause = enum_type.create_field_bound_where_clause(quote!(Clone), None);
+
+    assert_quote_snapshot!(struct_where_clause);
+    assert_quote_snapshot!(enum_where_clause);
+}
+
+#[test]
+fn generic_params_set_operations() {
+    let declaration = parse_declaration(quote!(
+        struct Hello<'a, T, const N: usize> {
+            a: &'a [T; N],
+        }
+    ));
+    let generic_params = declaration.generic_params().unwrap().clone();
+
+    let fresh_lifetime = generic_params.fresh_lifetime();
+    let fresh_ty_param = generic_params.fresh_ty_param();
+    let mut inserted_params = generic_params
+        .clone()
+        .with_param(fresh_ty_param)
+        .with_param(fresh_lifetime);
+    let removed_param = inserted_params.remove_param("T").unwrap();
+    assert_eq!(removed_param.name, "T");
+    assert!(inserted_params.remove_param("'T").is_none());
+
+    let other_params = parse_struct_declaration(quote!(
+        struct World<'b, T, U: Clone>(&'b T, U);
+    ))
+    .generic_params
+    .unwrap();
+    let merged_params = generic_params.clone().merge(other_params);
+
+    let mut name_gen = NameGen::from_generic_params(&merged_params);
+    assert_eq!(name_gen.ty_param("T").name, "__T");
+    assert_eq!(name_gen.ty_param("T").name, "__T1");
+    assert_eq!(merged_params.fresh_lifetime().name, "__a");
+
+    assert_quote_snapshot!(inserted_params);
+    assert_quote_snapshot!(merged_params);
+}
+
+#[test]
+#[should_panic = "name `T` is already used"]
+fn add_duplicate_param() {
+    let struct_type = parse_struct_declaration(quote!(
+        struct Hello<T>(T);
+    ));
+
+    struct_type.with_param(GenericParam::ty("T"));
+}
+
+#[test]
+fn substitute_generic() {
+    let mut declaration = parse_declaration_checked(quote!(
+        enum Hello<'a, T: Iterator<Item = &'a u8> + 'a, U = T, const N: usize = 4>
+        where
+            T::Item: Clone,
+            for<'b> &'b T: IntoIterator,
+        {
+            A(a::T, <X as Tr>::T, T::Item, [T; N]),
+            B { b: &'a U },
+            C = N,
+        }
+    ));
+
+    let ty = TyExpr {
+        tokens: quote!(Vec<u8>).into_iter().collect(),
+    };
+    declaration.substitute_generic(&format_ident!("T"), ty);
+    assert_quote_snapshot!(declaration);
+
+    declaration.substitute_lifetime(&format_ident!("a"), Lifetime::new("static"));
+    declaration.substitute_const(&format_ident!("N"), quote!(1 + 2));
+    assert_quote_snapshot!(declaration);
+}
+
+#[test]
+fn field_views_and_shapes() {
+    let enum_type = parse_declaration_checked(quote!(
+        enum Shapes {
+            Unit,
+            Empty(),
+            Newtype(u8),
+            Tuple(#[attr] pub u8, String),
+            Named { pub(crate) a: u8, b: String },
+            EmptyNamed {},
+        }
+    ));
+    let enum_type = enum_type.as_enum().unwrap();
+
+    let shapes: Vec<_> = enum_type
+        .variants
+        .items()
+        .map(|variant| {
+            let fields = &variant.contents;
+            (
+                variant.name.to_string(),
+                fields.len(),
+                fields.is_unit(),
+                fields.is_newtype(),
+                fields.is_tuple(),
+                fields.is_named(),
+                fields.is_empty(),
+            )
+        })
+        .collect();
+    assert_debug_snapshot!(shapes);
+
+    let fields: Vec<_> = enum_type
+        .variants
+        .items()
+        .flat_map(|variant| variant.contents.iter())
+        .collect();
+    let members: Vec<_> = fields.iter().map(|field| field.member()).collect();
+    assert_debug_snapshot!(fields);
+    assert_quote_snapshot!(quote!(#(self.#members),*));
+}
+
+#[test]
+fn binding_patterns() {
+    let struct_type = parse_struct_declaration(quote!(
+        struct Hello(u8, String);
+    ));
+    let struct_match = struct_type.each_field(BindingMode::Ref, |binding| {
+        let ident = &binding.ident;
+        quote!(f(#ident);)
+    });
+    assert_quote_snapshot!(struct_match);
+
+    let enum_type = parse_declaration_checked(quote!(
+        #[non_exhaustive]
+        enum Hello {
+            A,
+            B(u8, String),
+            C { c: u8, d: String },
+        }
+    ));
+    let enum_type = enum_type.as_enum().unwrap();
+    let enum_match = enum_type.each_field(BindingMode::RefMut, |binding| {
+        let ident = &binding.ident;
+        let member = binding.field.member();
+        quote!(f(#member, #ident);)
+    });
+    assert_quote_snapshot!(enum_match);
+}
+
+#[test]
+fn binding_patterns_in_methods() {
+    let expected = checked_quote!(
+        struct Hello(u8, String);
+        impl Hello {
+            fn by_ref_mut(&mut self) {
+                match *self {
+                    Self(ref mut __binding_0, ref mut __binding_1) => {
+                        let _ = __binding_0;
+                        let _ = __binding_1;
+                    }
+                }
+            }
+        }
+        enum World {
+            A,
+            B(u8, String),
+            C { c: u8 },
+        }
+        impl World {
+            fn by_ref(&self) {
+                match *self {
+                    Self::A => {},
+                    Self::B(ref __binding_0, ref __binding_1) => {
+                        let _ = __binding_0;
+                        let _ = __binding_1;
+                    },
+                    Self::C { c: ref __binding_0 } => {
+                        let _ = __binding_0;
+                    },
+                }
+            }
+            fn by_move(self) {
+                match self {
+                    Self::A => {},
+                    Self::B(__binding_0, __binding_1) => {
+                        let _ = __binding_0;
+                        let _ = __binding_1;
+                    },
+                    Self::C { c: __binding_0 } => {
+                        let _ = __binding_0;
+                    },
+                }
+            }
+        }
+    );
+
+    let body = |binding: &Binding| {
+        let ident = &binding.ident;
+        quote!(let _ = #ident;)
+    };
+    let struct_decl = quote!(
+        struct Hello(u8, String);
+    );
+    let struct_type = parse_declaration(struct_decl.clone());
+    let by_ref_mut = struct_type
+        .as_struct()
+        .unwrap()
+        .each_field(BindingMode::RefMut, body);
+    let enum_decl = quote!(
+        enum World {
+            A,
+            B(u8, String),
+            C { c: u8 },
+        }
+    );
+    let enum_type = parse_declaration(enum_decl.clone());
+    let enum_type = enum_type.as_enum().unwrap();
+    let by_ref = enum_type.each_field(BindingMode::Ref, body);
+    let by_move = enum_type.each_field(BindingMode::Move, body);
+
+    let generated = quote!(
+        #struct_decl
+        impl Hello {
+            fn by_ref_mut(&mut self) {
+                #by_ref_mut
+            }
+        }
+        #enum_decl
+        impl World {
+            fn by_ref(&self) {
+                #by_ref
+            }
+            fn by_move(self) {
+                #by_move
+            }
+        }
+    );
+    assert_eq!(generated.to_string(), expected.to_string());
+}
+
+#[test]
+fn impl_trait() {
+    let declaration = parse_declaration_checked(quote!(
+        enum Hello<'a, T: Clone, const N: usize>
+        where
+            T: Default,
+        {
+            A(&'a [T; N]),
+            B,
+        }
+    ));
+
+    let impl_block = declaration.impl_trait(
+        quote!(MyTrait),
+        quote!(
+            fn hello() {}
+        ),
+    );
+    assert_quote_snapshot!(impl_block);
+
+    let options = ImplTraitOptions::default()
+        .with_param(GenericParam::lifetime("de"))
+        .with_where_item(WhereClauseItem::parse(quote!('de: 'a)));
+    let impl_block = declaration.impl_trait_with(quote!(Deserialize<'de>), quote!(), &options);
+    assert_quote_snapshot!(impl_block);
+
+    let declaration = parse_declaration_checked(quote!(
+        struct World(u8);
+    ));
+    let impl_block = declaration.impl_trait(quote!(MyTrait), quote!());
+    assert_quote_snapshot!(impl_block);
+}
+
+#[test]
+fn discriminant_values() {
+    let enum_type = parse_declaration_checked(quote!(
+        #[repr(i16)]
+        enum Hello {
+            A = -0x10,
+            B,
+            C = 0b11_i16,
+            D = (C + 1) * 2,
+            E = Self::A as u8 as i16 | 1 << 8,
+            F = some_const(),
+            G,
+            H = !0,
+            I = foo::<u8, u16>(),
+        }
+    ));
+    let values = enum_type.as_enum().unwrap().discriminant_values();
+    assert_debug_snapshot!(values);
+
+    let enum_type = parse_declaration_checked(quote!(
+        #[repr(u8)]
+        enum Overflow {
+            A = 254,
+            B,
+            C,
+        }
+    ));
+    let error = enum_type
+        .as_enum()
+        .unwrap()
+        .discriminant_values()
+        .unwrap_err();
+    assert_quote_snapshot!(error.to_compile_error());
+
+    let enum_type = parse_declaration_checked(quote!(
+        enum Duplicate {
+            A = 1,
+            B = 0,
+            C,
+        }
+    ));
+    let error = enum_type
+        .as_enum()
+        .unwrap()
+        .discriminant_values()
+        .unwrap_err();
+    assert_eq!(
+        error.message(),
+        "discriminant value `1` assigned more than once, first to `A`"
+    );
+}
+
+#[test]
+fn parse_repr() {
+    let struct_type = parse_struct_declaration(quote!(
+        #[repr(C, packed(2))]
+        struct Hello(u8, u32);
+    ));
+    assert_debug_snapshot!(struct_type.repr());
+
+    let enum_type = parse_declaration_checked(quote!(
+        #[repr(C)]
+        #[derive(Clone)]
+        #[repr(u8)]
+        enum Hello {
+            A,
+            B,
+        }
+    ));
+    assert_debug_snapshot!(enum_type.as_enum().unwrap().repr());
+
+    let struct_type = parse_struct_declaration(quote!(
+        #[repr(transparent)]
+        struct Wrapper<T>(u32, PhantomData<T>, [u8; 0], ());
+    ));
+    assert!(struct_type.repr().unwrap().is_transparent());
+
+    let errors: Vec<_> = [
+        quote!(
+            #[repr(packed)]
+            #[repr(align(4))]
+            struct Hello;
+        ),
+        quote!(
+            #[repr(transparent)]
+            struct Hello(u32, u32);
+        ),
+        quote!(
+            #[repr(u8)]
+            struct Hello;
+        ),
+        quote!(
+            #[repr(align(3))]
+            struct Hello;
+        ),
+        quote!(
+            #[repr(C, transparent)]
+            struct Hello;
+        ),
+        quote!(
+            #[repr(simd)]
+            struct Hello;
+        ),
+    ]
+    .into_iter()
+    .map(|tokens| {
+        let struct_type = parse_struct_declaration(tokens);
+        struct_type.repr().unwrap_err().to_string()
+    })
+    .collect();
+    assert_debug_snapshot!(errors);
+}
+
+#[test]
+fn apply_cfg() {
+    let declaration = parse_declaration_checked(quote!(
+        #[cfg_attr(all(unix, not(test)), derive(Debug), cfg_attr(feature = "x", repr(u8)))]
+        #[cfg_attr(windows, derive(Clone))]
+        enum Hello<#[cfg(test)] T> {
+            #[cfg(any(windows, feature = "x"))]
+            A,
+            #[cfg(windows)]
+            B,
+            C(#[cfg(test)] T, #[cfg(unix)] u8),
+        }
+    ));
+
+    let gated_variants: Vec<_> = declaration
+        .as_enum()
+        .unwrap()
+        .variants
+        .items()
+        .map(|variant| (variant.name.to_string(), variant.is_cfg_gated()))
+        .collect();
+    assert_debug_snapshot!(gated_variants);
+
+    let options = CfgOptions::new()
+        .with_name("unix")
+        .with_key_value("feature", "x");
+    let mut configured_declaration = declaration.clone();
+    assert!(configured_declaration.apply_cfg(&options).unwrap());
+    assert_quote_snapshot!(configured_declaration);
+
+    let mut declaration = parse_declaration_checked(quote!(
+        #[cfg(feature = "y")]
+        struct Hello;
+    ));
+    assert!(!declaration.apply_cfg(&options).unwrap());
+
+    let predicate = CfgPredicate::parse(quote!(any(feature = r"std", feature = "a\"b"))).unwrap();
+    assert_eq!(
+        predicate,
+        CfgPredicate::Any(vec![
+            CfgPredicate::KeyValue("feature".to_string(), "std".to_string()),
+            CfgPredicate::KeyValue("feature".to_string(), "a\"b".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn well_known_attributes() {
+    let declaration = parse_declaration_checked(quote!(
+        #[derive(Clone, Debug, serde::Serialize, Default)]
+        #[non_exhaustive]
+        #[deprecated(since = "1.2.0", note = "use \"World\" instead")]
+        #[must_use = "this is important"]
+        #[doc(hidden)]
+        #[allow(dead_code, clippy::all)]
+        #[deny(dead_code)]
+        enum Hello {
+            A,
+            #[default]
+            #[deprecated = "don't"]
+            B {
+                #[allow(unused)]
+                #[doc = "hidden"]
+                b: u8,
+            },
+        }
+    ));
+
+    let derives: Vec<_> = declaration
+        .derives()
+        .iter()
+        .map(|derive| quote!(#derive).to_string())
+        .collect();
+    assert_debug_snapshot!(derives);
+    assert!(declaration.has_derive("serde :: Serialize"));
+    assert!(!declaration.has_derive("Serialize"));
+
+    assert!(declaration.is_non_exhaustive());
+    assert_debug_snapshot!(declaration.deprecated());
+    assert!(declaration.is_must_use());
+    assert_eq!(
+        declaration.must_use_message().as_deref(),
+        Some("this is important")
+    );
+    assert!(declaration.is_doc_hidden());
+    assert_eq!(declaration.lint_level("dead_code"), Some(LintLevel::Deny));
+    assert_eq!(
+        declaration.lint_level("clippy::all"),
+        Some(LintLevel::Allow)
+    );
+    assert_eq!(declaration.lint_level("unused"), None);
+    assert_eq!(declaration.lint_attributes().len(), 2);
+
+    let enum_type = declaration.as_enum().unwrap();
+    let variant = enum_type.default_variant().unwrap();
+    assert_eq!(variant.name, "B");
+    assert!(!variant.is_non_exhaustive());
+    assert_debug_snapshot!(variant.deprecated());
+
+    let field = variant.contents.iter().next().unwrap();
+    assert_eq!(field.lint_level("unused"), Some(LintLevel::Allow));
+    assert!(!field.is_doc_hidden());
+}
+
+#[test]
+fn strip_and_retain_attributes() {
+    let mut declaration = parse_declaration_checked(quote!(
+        #[my_attr]
+        #[doc = "Hello"]
+        #[derive(Clone)]
+        fn hello<#[my_attr] T>(
+            #[my_attr(skip)]
+            #[allow(unused)]
+            a: T,
+        ) {
+        }
+    ));
+
+    declaration.strip_attributes("my_attr");
+    declaration.retain_attributes(|attribute| !attribute.has_path("derive"));
+    declaration
+        .attributes_mut()
+        .push(Attribute::new("allow", quote!((dead_code))));
+    assert_quote_snapshot!(declaration);
+
+    let mut struct_type = parse_struct_declaration(quote!(
+        struct Hello {
+            #[my_attr]
+            #[serde(rename = "b")]
+            a: u8,
+        }
+    ));
+    struct_type.retain_attributes(|_| false);
+    let mut declaration = Declaration::Struct(struct_type);
+    declaration.strip_attributes("my_attr");
+    assert_quote_snapshot!(declaration);
+}
+
+#[test]
+fn punctuated_collection_api() {
+    let struct_type = parse_struct_declaration(quote!(
+        struct Hello(A, B, C);
+    ));
+    let mut fields = match struct_type.fields {
+        StructFields::Tuple(fields) => fields.fields,
+        _ => unreachable!(),
+    };
+
+    assert_eq!(fields.len(), 3);
+    assert!(!fields.is_empty());
+    assert_quote_snapshot!(fields[0]);
+    assert_quote_snapshot!(fields.last().unwrap());
+
+    let removed_field = fields.remove(1);
+    assert_quote_snapshot!(removed_field);
+    assert_quote_snapshot!(fields);
+
+    let comma = proc_macro2::Punct::new(',', proc_macro2::Spacing::Alone);
+    fields.insert(0, removed_field.clone(), Some(comma));
+    assert_quote_snapshot!(fields);
+
+    for field in &mut fields {
+        field.vis_marker = None;
+    }
+    fields.retain(|field| quote::ToTokens::to_token_stream(&field.ty).to_string() != "A");
+    let pairs: Vec<_> = fields
+        .pairs()
+        .map(|(field, punct)| (quote!(#field).to_string(), punct.map(|p| p.as_char())))
+        .collect();
+    assert_debug_snapshot!(pairs);
+
+    let popped_field = fields.pop().unwrap();
+    assert_quote_snapshot!(popped_field);
+
+    let collected_fields: Punctuated<TupleField> = vec![removed_field.clone(), popped_field]
+        .into_iter()
+        .collect();
+    assert_quote_snapshot!(collected_fields);
+
+    fields.extend(collected_fields);
+    assert_eq!(fields.iter().count(), 3);
+    fields.clear();
+    assert!(fields.is_empty());
+    assert!(fields.first().is_none());
+}
+
+#[test]
+fn punctuated_separators() {
+    let struct_type = parse_declaration_checked(quote!(
+        struct Hello<T>
+        where
+            T: Clone + Send,
+        {
+            a: T,
+        }
+    ));
+    let where_clause = match struct_type {
+        Declaration::Struct(struct_type) => struct_type.where_clause.unwrap(),
+        _ => unreachable!(),
+    };
+    let mut predicate = match where_clause.items.first() {
+        Some(WhereClauseItem::Ty(predicate)) => predicate.clone(),
+        _ => unreachable!(),
+    };
+    assert!(predicate.bounds.punct().all(Plus::is));
+
+    let bound = predicate.bounds.pop().unwrap();
+    predicate.bounds.push(bound.clone(), Some(Plus::punct()));
+    predicate.bounds.push(bound, None);
+    assert_quote_snapshot!(predicate);
+
+    let patterns: Punctuated<TyExpr, Or> = ["A", "B"]
+        .iter()
+        .map(|name| TyExpr {
+            tokens: vec![format_ident!("{}", name).into()],
+        })
+        .collect();
+    assert_eq!(quote!(#patterns).to_string(), "A | B");
+
+    let mut statements: Punctuated<TyExpr, Semi> = patterns.into_iter().collect();
+    statements.skip_last = false;
+    assert_eq!(quote!(#statements).to_string(), "A ; B ;");
+}
+
+#[test]
+#[should_panic = "cannot use ',' as a separator in a '+'-separated list"]
+fn punctuated_wrong_separator() {
+    let mut bounds: Punctuated<TyExpr, Plus> = Punctuated::new();
+    bounds.push(TyExpr { tokens: vec![] }, Some(Comma::punct()));
+}
+
+#[test]
+fn build_declarations() {
+    let span = proc_macro2::Span::mixed_site();
+    let struct_type = Struct::new_spanned("FooBuilder", span)
+        .with_attribute(Attribute::new("derive", quote!((Default))))
+        .with_vis(VisMarker::public_spanned(span))
+        .with_param(GenericParam::ty("T"))
+        .with_named_field(NamedField::new("x", quote!(Option<T>)))
+        .with_named_field(
+            NamedField::new("y", quote!(Vec<u8>))
+                .with_vis(VisMarker::restricted_spanned(quote!(crate), span)),
+        );
+    assert_quote_snapshot!(struct_type);
+
+    let tuple_struct = Struct::new("Wrapper")
+        .with_param(GenericParam::ty("T"))
+        .with_tuple_field(TupleField::new_spanned(quote!(T), span))
+        .with_where_item(WhereClauseItem::parse(quote!(T: Clone)));
+    assert_quote_snapshot!(tuple_struct);
+
+    let enum_type = Enum::new("Shape")
+        .with_variant(EnumVariant::new("Empty").with_discriminant(quote!(1 << 4)))
+        .with_variant(EnumVariant::new("Circle").with_tuple_field(TupleField::new(quote!(f32))))
+        .with_variant(
+            EnumVariant::new("Rect")
+                .with_named_field(NamedField::new("w", quote!(f32)))
+                .with_named_field(NamedField::new("h", quote!(f32))),
+        );
+    assert_quote_snapshot!(enum_type);
+
+    let union_type = Union::new("Bits")
+        .with_field(NamedField::new("int", quote!(u32)))
+        .with_field(NamedField::new("float", quote!(f32)));
+    assert_quote_snapshot!(union_type);
+
+    let function = Function::new("build")
+        .with_vis(VisMarker::public())
+        .with_param(GenericParam::ty("T"))
+        .with_fn_param(FunctionParameter::new_spanned("value", quote!(T), span))
+        .with_return_ty(quote!(FooBuilder<T>))
+        .with_where_item(WhereClauseItem::parse(quote!(T: Default)))
+        .with_body(quote!(FooBuilder {
+            x: Some(value),
+            y: Vec::new()
+        }));
+    assert_quote_snapshot!(function);
+}
+
+#[test]
+#[should_panic = "cannot add a named field to a tuple struct"]
+fn build_struct_mixed_fields() {
+    Struct::new("Hello")
+        .with_tuple_field(TupleField::new(quote!(u8)))
+        .with_named_field(NamedField::new("a", quote!(u8)));
+}
+
+#[test]
+#[should_panic = "invalid attribute path `a b`"]
+fn new_attribute_invalid_path() {
+    Attribute::new("rustfmt::skip", quote!());
+    Attribute::new("::serde::rename", quote!((= "a")));
+    Attribute::new("a b", quote!());
+}
+
+#[test]
+fn map_fields_mirror_types() {
+    let struct_type = parse_struct_declaration(quote!(
+        /// Docs
+        #[derive(Clone)]
+        #[cfg(feature = "foo")]
+        pub struct Foo<T>(#[cfg(unix)] pub T, #[serde(skip)] u8, String)
+        where
+            T: Clone;
+    ));
+    let partial = struct_type
+        .map_fields("FooPartial", |field| {
+            let ty = field.ty;
+            if field.index == 1 {
+                None
+            } else {
+                Some(quote!(Option<#ty>))
+            }
+        })
+        .with_attribute(Attribute::new("derive", quote!((Default))));
+    assert_quote_snapshot!(partial);
+
+    let enum_type = match parse_declaration(quote!(
+        #[repr(u8)]
+        #[derive(Debug)]
+        enum Hello<'a> {
+            #[serde(rename = "a")]
+            A = 1,
+            B {
+                b: &'a str,
+            },
+        }
+    )) {
+        Declaration::Enum(enum_type) => enum_type,
+        _ => unreachable!(),
+    };
+    let owned = enum_type.map_variants("HelloOwned", |variant| {
+        Some(variant.map_fields(|_field| Some(quote!(String))))
+    });
+    assert_quote_snapshot!(owned);
+
+    let struct_type = parse_struct_declaration(quote!(
+        #[derive(Debug)]
+        #[serde(bound = "")]
+        struct Foo<'a, 'b: 'a, T: Into<U>, U = T, const N: usize>
+        where
+            T: Clone,
+            U: 'a,
+        {
+            #[serde(skip)]
+            a: &'a [u8; N],
+            b: &'b T,
+            c: U,
+        }
+    ));
+    let patch = struct_type.map_fields_with(
+        "FooPatch",
+        |attribute| attribute.has_path("serde"),
+        |field| {
+            let ty = field.ty;
+            match field.name {
+                Some(name) if name == "c" => None,
+                _ => Some(quote!(Option<#ty>)),
+            }
+        },
+    );
+    assert_quote_snapshot!(patch);
+}
+
+#[test]
+fn map_spans_reaches_every_token() {
+    fn count_tokens(tokens: TokenStream) -> usize {
+        tokens
+            .into_iter()
+            .map(|token| match token {
+                proc_macro2::TokenTree::Group(group) => 1 + count_tokens(group.stream()),
+                _ => 1,
+            })
+            .sum()
+    }
+
+    let mut declarations = vec![
+        parse_declaration(quote!(
+            #[derive(Debug)]
+            pub(crate) struct Foo<'a, T: Clone = u8>
+            where
+                T: Default + 'a,
+                for<'b> &'b T: Debug,
+            {
+                /// Docs
+                pub a: &'a T,
+                b: Vec<u8>,
+            }
+        )),
+        parse_declaration(quote!(
+            enum Bar<const N: usize> {
+                A(#[serde(skip)] [u8; N]),
+                B = 1 << 4,
+            }
+        )),
+        parse_declaration(quote!(
+            pub async unsafe fn baz<T>(a: T, b: u8) -> Option<T>
+            where
+                T: Clone,
+            {
+                if b > 0 {
+                    Some(a)
+                } else {
+                    None
+                }
+            }
+        )),
+    ];
+
+    for declaration in &mut declarations {
+        let mut calls = 0;
+        declaration.map_spans(|span| {
+            calls += 1;
+            span.resolved_at(proc_macro2::Span::mixed_site())
+        });
+        declaration.set_span(proc_macro2::Span::mixed_site());
+        let mut token_count = count_tokens(quote!(#declaration));
+        if let Declaration::Function(function) = declaration {
+            // `fn`, `(...)`, `->`, the colons of the parameters and the `;`
+            // of prototypes aren't stored in the declaration.
+            let arrow_count = if function.return_ty.is_some() { 2 } else { 0 };
+            let semicolon_count = if function.body.is_none() { 1 } else { 0 };
+            token_count -= 1 + 1 + arrow_count + function.params.len() + semicolon_count;
+        }
+        assert_eq!(calls, token_count);
+    }
+}
+
+#[test]
+fn spanned_nodes() {
+    use crate::Spanned;
+
+    // Tests enable proc-macro2's `span-locations` feature, so tokens parsed
+    // from a string have distinct spans, unlike the ones built by `quote!`.
+    fn line_column(span: Span) -> (usize, usize) {
+        let start = span.start();
+        (start.line, start.column)
+    }
+
+    let source = "#[repr(u8, C, foo)] enum Hello<'a, T: Clone> { A { a: &'a T } }";
+    let position = |needle: &str| (1, source.find(needle).unwrap());
+    let mut declaration = parse_declaration(source.parse().unwrap());
+
+    let enum_type = declaration.as_enum().unwrap();
+    let variant = &enum_type.variants[0];
+    let field = match &variant.contents {
+        StructFields::Named(fields) => &fields.fields[0],
+        _ => unreachable!(),
+    };
+    let generic_param = &enum_type.generic_params.as_ref().unwrap().params[1];
+    let attribute = &enum_type.attributes[0];
+
+    // On stable compilers, `span` returns the span of the node's first token.
+    assert_eq!(line_column(declaration.span()), position("#"));
+    assert_eq!(line_column(attribute.span()), position("#"));
+    assert_eq!(line_column(generic_param.span()), position("T:"));
+    assert_eq!(line_column(variant.span()), position("A {"));
+    assert_eq!(line_column(field.span()), position("a:"));
+    assert_eq!(line_column(field.ty.span()), position("&"));
+
+    let error = enum_type.repr().unwrap_err();
+    assert_eq!(error.message(), "unrecognized representation hint");
+    assert_eq!(line_column(error.span()), position("foo"));
+
+    let other_span = "\n    other"
+        .parse::<TokenStream>()
+        .unwrap()
+        .into_iter()
+        .next()
+        .unwrap()
+        .span();
+    assert_eq!(line_column(other_span), (2, 4));
+
+    let enum_type = match &mut declaration {
+        Declaration::Enum(enum_type) => enum_type,
+        _ => unreachable!(),
+    };
+    enum_type.variants[0].set_span(other_span);
+    enum_type.attributes[0].map_spans(|span| {
+        if line_column(span) == position("foo") {
+            other_span
+        } else {
+            span
+        }
+    });
+    assert_eq!(line_column(enum_type.variants[0].span()), (2, 4));
+    assert_eq!(line_column(enum_type.attributes[0].span()), position("#"));
+    let error = enum_type.repr().unwrap_err();
+    assert_eq!(line_column(error.span()), (2, 4));
+}
+
+#[test]
+fn name_gen_avoids_used_names() {
+    let declaration = parse_declaration(quote!(
+        enum Hello<'__a, __T: Into<__T1>> {
+            __value { r#__value1: u8 },
+            B(__T),
+        }
+    ));
+    let mut name_gen = NameGen::new(&declaration).with_span(proc_macro2::Span::call_site());
+    name_gen.reserve("__value2");
+
+    let lifetime = name_gen.lifetime("a");
+    let ty_param = name_gen.ty_param("T");
+    let locals = [name_gen.ident("value"), name_gen.ident("value")];
+    assert_eq!(quote!(#lifetime).to_string(), "'__a1");
+    assert_eq!(ty_param.name, "__T2");
+    assert_eq!(locals[0], "__value3");
+    assert_eq!(locals[1], "__value4");
+}
+
+#[test]
+fn rename_rules() {
+    let names = [
+        "field_name",
+        "VariantName",
+        "HTTPServer",
+        "vec3D",
+        "Utf8Error",
+        "__private",
+        "type_",
+        "ÉtéIndien",
+    ];
+    let renamed: Vec<Vec<String>> = RenameRule::ALL
+        .iter()
+        .map(|rule| {
+            assert_eq!(RenameRule::from_name(rule.as_str()), Some(*rule));
+            names.iter().map(|name| rule.apply(name)).collect()
+        })
+        .collect();
+    assert_debug_snapshot!(renamed);
+
+    let enum_type = parse_declaration(quote!(
+        enum Hello {
+            HelloWorld { r#type: u8 },
+        }
+    ));
+    let variant = &enum_type.as_enum().unwrap().variants[0];
+    assert_eq!(variant.renamed(RenameRule::KebabCase), "hello-world");
+    let field = variant.contents.iter().next().unwrap();
+    assert_eq!(
+        field.renamed(RenameRule::ScreamingSnakeCase).as_deref(),
+        Some("TYPE")
+    );
+    assert_eq!(RenameRule::from_name("Snake_Case"), None);
+}
+
+#[test]
+fn raw_identifiers() {
+    let declaration = parse_declaration(quote!(
+        struct r#Match<r#in> {
+            r#type: r#in,
+            name: String,
+        }
+    ));
+    assert_eq!(declaration.name().to_string(), "r#Match");
+    assert_eq!(declaration.name_unraw().to_string(), "Match");
+
+    let struct_type = declaration.as_struct().unwrap();
+    assert_eq!(struct_type.name_unraw().to_string(), "Match");
+    let params = &struct_type.generic_params.as_ref().unwrap().params;
+    assert_eq!(params[0].name_unraw().to_string(), "in");
+
+    let names: Vec<_> = struct_type
+        .fields
+        .iter()
+        .map(|field| field.name_unraw().unwrap().to_string())
+        .collect();
+    assert_eq!(names, ["type", "name"]);
+
+    let ident = match quote!(r#fn).into_iter().next() {
+        Some(TokenTree::Ident(ident)) => ident,
+        _ => unreachable!(),
+    };
+    assert!(ident.is_raw());
+    assert!(!ident.unraw().is_raw());
+
+    let enum_type = parse_declaration(quote!(
+        enum Token {
+            r#loop,
+            r#await(u8),
+        }
+    ));
+    let variants: Vec<_> = enum_type
+        .as_enum()
+        .unwrap()
+        .variants
+        .iter()
+        .map(|variant| variant.name_unraw().to_string())
+        .collect();
+    assert_eq!(variants, ["loop", "await"]);
+
+    // Only a keyword since the 2018 edition.
+    let declaration = parse_declaration(quote!(
+        struct async;
+    ));
+    assert_eq!(declaration.name().to_string(), "async");
+}
+
+#[test]
+#[should_panic = "found keyword `type`"]
+fn parse_keyword_declaration_name() {
+    let name = Ident::new("type", Span::call_site());
+    parse_declaration(quote!(
+        struct #name;
+    ));
+}
+
+// ========
+// VISITORS
+// ========
+
+#[test]
+fn visit_declaration() {
+    use crate::visit::{self, Visit};
+
+    #[derive(Default)]
+    struct Collector {
+        lifetimes: Vec<String>,
+        fields_mentioning_t: Vec<String>,
+        serde_attributes: usize,
+    }
+
+    impl<'ast> Visit<'ast> for Collector {
+        fn visit_named_field(&mut self, field: &'ast crate::NamedField) {
+            if field.ty.mentions_any(&[&format_ident!("T")]) {
+                self.fields_mentioning_t.push(field.name.to_string());
+            }
+            visit::walk_named_field(self, field);
+        }
+
+        fn visit_attribute(&mut self, attribute: &'ast Attribute) {
+            if attribute.has_path("serde") {
+                self.serde_attributes += 1;
+            }
+        }
+
+        fn visit_lifetime(&mut self, lifetime: &Lifetime) {
+            self.lifetimes.push(lifetime.name.to_string());
+        }
+    }
+
+    let declaration = parse_declaration(quote!(
+        enum Hello<'a, 'b: 'a, T>
+        where
+            'a: 'b,
+            T: Clone + 'a,
+        {
+            A {
+                #[serde(skip)]
+                a: Vec<T>,
+                b: &'a str,
+            },
+            B {
+                #[serde(default)]
+                c: (u8, &'b T),
+            },
+            C(T),
+        }
+    ));
+
+    let mut collector = Collector::default();
+    collector.visit_declaration(&declaration);
+    assert_eq!(
+        collector.lifetimes,
+        ["a", "b", "a", "a", "b", "a", "a", "b"]
+    );
+    assert_eq!(collector.fields_mentioning_t, ["a", "c"]);
+    assert_eq!(collector.serde_attributes, 2);
+}
+
+#[test]
+fn visit_mut_declaration() {
+    use crate::visit_mut::{self, VisitMut};
+
+    // Renames `T` to `T2`, and wraps field types in `Option`.
+    struct Rewriter;
+
+    fn rename(tokens: &mut Vec<TokenTree>) {
+        for token in tokens {
+            if let TokenTree::Ident(ident) = token {
+                if ident == "T" {
+                    *ident = format_ident!("T2");
+                }
+            }
+        }
+    }
+
+    impl VisitMut for Rewriter {
+        fn visit_generic_param_mut(&mut self, param: &mut GenericParam) {
+            param.name = format_ident!("{}2", param.name);
+            visit_mut::walk_generic_param_mut(self, param);
+        }
+
+        fn visit_generic_bound_mut(&mut self, bound: &mut GenericBound) {
+            rename(&mut bound.tokens);
+        }
+
+        fn visit_attribute_mut(&mut self, attribute: &mut Attribute) {
+            if attribute.has_path("doc") {
+                *attribute = Attribute::new("doc", quote!(= "Rewritten"));
+            }
+        }
+
+        fn visit_ty_expr_mut(&mut self, ty: &mut TyExpr) {
+            rename(&mut ty.tokens);
+            ty.tokens = quote!(Option<#ty>).into_iter().collect();
+        }
+    }
+
+    let mut declaration = parse_declaration(quote!(
+        /// Hello
+        enum Hello<T: PartialEq<T>> {
+            A(T),
+            B { b: u8 },
+        }
+    ));
+    Rewriter.visit_declaration_mut(&mut declaration);
+    assert_quote_snapshot!(declaration);
+}
+
+#[test]
+fn fold_declaration() {
+    use crate::fold::{self, Fold};
+
+    struct AllowDeadCode;
+
+    impl Fold for AllowDeadCode {
+        fn fold_tuple_field(&mut self, mut field: TupleField) -> TupleField {
+            field
+                .attributes
+                .push(Attribute::new("allow", quote!((dead_code))));
+            fold::walk_tuple_field(self, field)
+        }
+
+        fn fold_attribute(&mut self, mut attribute: Attribute) -> Attribute {
+            if attribute.has_path("allow") {
+                attribute.child_tokens = quote!(allow(unused)).into_iter().collect();
+            }
+            attribute
+        }
+    }
+
+    let declaration = parse_declaration(quote!(
+        struct Hello<T>(u8, T)
+        where
+            T: Clone;
+    ));
+    let declaration = AllowDeadCode.fold_declaration(declaration);
+    assert_quote_snapshot!(declaration);
+}
@@ -1,9 +1,9 @@
 #![allow(missing_docs)]
 
-use proc_macro2::{Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+use proc_macro2::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
 use quote::{ToTokens, TokenStreamExt as _};
 
-use crate::Punctuated;
+use crate::punctuated::{Plus, Punctuated};
 
 /// The declaration of a Rust type.
 ///
@@ -214,6 +214,158 @@ pub struct NamedField {
     pub ty: TyExpr,
 }
 
+/// A view over a field of a [`Struct`], [`EnumVariant`] or [`Union`], named or not.
+///
+/// Returned by [`StructFields::iter`], [`TupleStructFields::iter`] and
+/// [`NamedStructFields::iter`].
+#[derive(Clone, Copy, Debug)]
+pub struct Field<'a> {
+    /// Position of the field in its declaration.
+    pub index: usize,
+    /// Name of the field, if it is a named field.
+    pub name: Option<&'a Ident>,
+    pub attributes: &'a [Attribute],
+    pub vis_marker: Option<&'a VisMarker>,
+    pub ty: &'a TyExpr,
+}
+
+/// The part after the dot in a field access expression, eg `self.foo` or `self.0`.
+#[derive(Clone, PartialEq, Eq)]
+pub enum Member {
+    Named(Ident),
+    Unnamed(usize),
+}
+
+/// How fields are bound in the patterns generated by eg [`Struct::each_field`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BindingMode {
+    /// `Self { a: __binding_0 }`
+    Move,
+    /// `Self { a: ref __binding_0 }`
+    Ref,
+    /// `Self { a: ref mut __binding_0 }`
+    RefMut,
+}
+
+/// A field bound to a local variable in a generated pattern.
+#[derive(Clone, Debug)]
+pub struct Binding<'a> {
+    pub field: Field<'a>,
+    /// Name of the local variable, eg `__binding_0`.
+    ///
+    /// Uses a mixed-site span, so it can't clash with identifiers written by
+    /// the macro's caller.
+    pub ident: Ident,
+}
+
+/// Additional generics for the impl blocks generated by [`Declaration::impl_trait_with`].
+///
+/// **Example:** for a `Deserialize<'de>` impl, add the `'de` lifetime with
+/// `ImplTraitOptions::default().with_param(GenericParam::lifetime("de"))`.
+#[derive(Clone, Debug, Default)]
+pub struct ImplTraitOptions {
+    /// Parameters added to the impl's generics, but not to the type's arguments.
+    pub extra_params: Vec<GenericParam>,
+    /// Items added to the impl's where clause.
+    pub extra_predicates: Vec<WhereClauseItem>,
+}
+
+/// Generates identifiers which don't collide with the names used in a
+/// [`Declaration`] or a [`GenericParams`], as returned by [`NameGen::new`]
+/// and [`NameGen::from_generic_params`].
+#[derive(Clone, Debug)]
+pub struct NameGen {
+    pub(crate) used_names: Vec<String>,
+    pub(crate) span: Span,
+}
+
+/// The layout of a type, merged from all its `#[repr(...)]` attributes.
+///
+/// Returned by eg [`Struct::repr`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Repr {
+    pub kind: ReprKind,
+    /// `packed` or `packed(N)`. `packed` is the same as `packed(1)`.
+    pub packed: Option<u32>,
+    /// `align(N)`.
+    pub align: Option<u32>,
+    /// The integer type of an enum's discriminant, eg `u8`.
+    pub int: Option<ReprInt>,
+}
+
+/// The main layout of a type: `Rust` (the default), `C` or `transparent`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReprKind {
+    #[default]
+    Rust,
+    C,
+    Transparent,
+}
+
+/// A primitive representation, eg `#[repr(u8)]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReprInt {
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Isize,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    Usize,
+}
+
+/// A case convention for names, with the same names as the ones of
+/// `#[serde(rename_all = "...")]`.
+///
+/// See [`RenameRule::apply`] for how names are split into words.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenameRule {
+    /// `lowercase`
+    LowerCase,
+    /// `UPPERCASE`
+    UpperCase,
+    /// `PascalCase`
+    PascalCase,
+    /// `camelCase`
+    CamelCase,
+    /// `snake_case`
+    SnakeCase,
+    /// `SCREAMING_SNAKE_CASE`
+    ScreamingSnakeCase,
+    /// `kebab-case`
+    KebabCase,
+    /// `SCREAMING-KEBAB-CASE`
+    ScreamingKebabCase,
+}
+
+/// The active configuration options used by [`Declaration::apply_cfg`], eg
+/// `unix` or `feature = "std"`.
+#[derive(Clone, Debug, Default)]
+pub struct CfgOptions {
+    pub(crate) names: Vec<String>,
+    pub(crate) key_values: Vec<(String, String)>,
+}
+
+/// The predicate of a `#[cfg(...)]` attribute.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CfgPredicate {
+    /// `unix`
+    Name(String),
+    /// `feature = "std"`
+    KeyValue(String, String),
+    /// `all(...)`
+    All(Vec<CfgPredicate>),
+    /// `any(...)`
+    Any(Vec<CfgPredicate>),
+    /// `not(...)`
+    Not(Box<CfgPredicate>),
+}
+
 // --- Token groups ---
 
 // TODO - parse better
@@ -235,6 +387,29 @@ pub struct Attribute {
     pub child_tokens: Vec<TokenTree>,
 }
 
+/// The contents of a `#[deprecated]` attribute.
+///
+/// **Example input:**
+///
+/// ```no_run
+/// #[deprecated(since = "1.2.0", note = "use `bar` instead")]
+/// # struct Foo;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Deprecated {
+    pub since: Option<String>,
+    pub note: Option<String>,
+}
+
+/// The level of a lint attribute, eg `#[allow(dead_code)]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+    Forbid,
+}
+
 /// Visibility marker, eg `pub`, `pub(crate)`, `pub(super)`, etc.
 #[derive(Clone)]
 pub struct VisMarker {
@@ -269,10 +444,12 @@ pub struct GenericParams {
 /// ```
 #[derive(Clone)]
 pub struct GenericParam {
+    pub attributes: Vec<Attribute>,
     /// Either `'` for lifetimes, `const` for const parameters, or None for type parameters.
     pub _prefix: Option<TokenTree>,
     pub name: Ident,
     pub bound: Option<GenericBound>,
+    pub default: Option<GenericDefault>,
 }
 
 /// A parameter bound in a type's generic list.
@@ -284,12 +461,33 @@ pub struct GenericBound {
     pub tokens: Vec<TokenTree>,
 }
 
+/// A default value in a type's generic list.
+///
+/// For instance, this is the `= i32` in `struct MyStruct<T = i32>(T);`
+#[derive(Clone)]
+pub struct GenericDefault {
+    pub _eq: Punct,
+    pub tokens: Vec<TokenTree>,
+}
+
 /// Generic arguments deduced from a type's [GenericParams].
 ///
 /// For instance, `<'a: 'static, T, U: Clone, const N: usize>` becomes `<'a, T, U, N>`.
 /// This is useful when creating wrapper types in derive macros.
 pub struct InlineGenericArgs<'a>(pub(crate) &'a GenericParams);
 
+/// Generic parameters as written after the `impl` keyword of an impl block.
+///
+/// For instance, `<'a: 'static, #[attr] T: Clone = i32, const N: usize>` becomes
+/// `<'a: 'static, T: Clone, const N: usize>`: bounds are kept, defaults and
+/// attributes are dropped. Nothing is emitted if the type isn't generic.
+pub struct ImplGenerics<'a>(pub(crate) Option<&'a GenericParams>);
+
+/// Generic arguments as written after the type's name in an impl block.
+///
+/// Same as [InlineGenericArgs], except nothing is emitted if the type isn't generic.
+pub struct TyGenerics<'a>(pub(crate) Option<&'a GenericParams>);
+
 /// All the stuff that comes after the `where` keyword.
 #[derive(Clone)]
 pub struct WhereClause {
@@ -305,9 +503,75 @@ pub struct WhereClause {
 /// struct MyStruct<T>(T) where T: Clone;
 /// ```
 #[derive(Clone)]
-pub struct WhereClauseItem {
-    pub left_side: Vec<TokenTree>,
-    pub bound: GenericBound,
+pub enum WhereClauseItem {
+    /// A lifetime predicate, eg `'a: 'b + 'c`.
+    Lifetime(LifetimePredicate),
+    /// A type predicate, eg `for<'de> T: Deserialize<'de>`.
+    Ty(TyPredicate),
+}
+
+/// A where clause item bounding a lifetime.
+///
+/// This is the `'a: 'b + 'c` in the following code:
+///
+/// ```
+/// struct MyStruct<'a, 'b, 'c>(&'a (), &'b (), &'c ()) where 'a: 'b + 'c;
+/// ```
+#[derive(Clone)]
+pub struct LifetimePredicate {
+    pub lifetime: Lifetime,
+    pub _colon: Punct,
+    pub bounds: Punctuated<Lifetime, Plus>,
+}
+
+/// A where clause item bounding a type.
+///
+/// This is the `for<'de> T::Item: Deserialize<'de> + Clone` in the following code:
+///
+/// ```no_run
+/// # trait Deserialize<'de> {}
+/// struct MyStruct<T: Iterator>(T) where for<'de> T::Item: Deserialize<'de> + Clone;
+/// ```
+#[derive(Clone)]
+pub struct TyPredicate {
+    pub for_binder: Option<ForBinder>,
+    pub left_side: TyExpr,
+    pub _colon: Punct,
+    pub bounds: Punctuated<TyBound, Plus>,
+}
+
+/// Higher-ranked lifetimes introduced before a type or a bound.
+///
+/// For instance, this is the `for<'de>` in `for<'de> T: Deserialize<'de>`.
+#[derive(Clone)]
+pub struct ForBinder {
+    pub _for: Ident,
+    pub params: GenericParams,
+}
+
+/// A lifetime, eg `'a` or `'static`.
+#[derive(Clone)]
+pub struct Lifetime {
+    pub _apostrophe: Punct,
+    pub name: Ident,
+}
+
+/// A single bound in a [`TyPredicate`].
+///
+/// For instance, `Clone` and `'a` are each a bound in `T: Clone + 'a`.
+#[derive(Clone, Debug)]
+pub enum TyBound {
+    Lifetime(Lifetime),
+    Trait(TraitBound),
+}
+
+/// A trait bound, eg `Clone`, `?Sized` or `for<'a> Fn(&'a T)`.
+#[derive(Clone)]
+pub struct TraitBound {
+    /// `?` token, if any.
+    pub _question: Option<Punct>,
+    pub for_binder: Option<ForBinder>,
+    pub path: TyExpr,
 }
 
 /// Type expression in a [`TupleField`] or [`NamedField`].
@@ -341,6 +605,15 @@ pub struct EnumDiscriminant {
     pub tokens: Vec<TokenTree>,
 }
 
+/// The value of an [`EnumVariant`]'s discriminant, as returned by [`Enum::discriminant_values`].
+#[derive(Clone)]
+pub enum DiscriminantValue {
+    /// A value computed from literals and earlier variants.
+    Int(i128),
+    /// An expression which couldn't be evaluated, eg `some_const() + 1`.
+    Expr(Vec<TokenTree>),
+}
+
 // --- Debug impls ---
 
 struct TokenRef<'a>(&'a TokenTree);
@@ -432,12 +705,24 @@ impl std::fmt::Debug for GenericParams {
 impl std::fmt::Debug for GenericParam {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut f = f.debug_struct("GenericParam");
+        f.field("attributes", &self.attributes);
         f.field("name", &self.name.to_string());
         f.field("bound", &self.bound);
+        f.field("default", &self.default);
         f.finish()
     }
 }
 
+impl std::fmt::Debug for GenericDefault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut list = f.debug_list();
+        for token in &self.tokens {
+            list.entry(&TokenRef(token));
+        }
+        list.finish()
+    }
+}
+
 impl std::fmt::Debug for GenericBound {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut list = f.debug_list();
@@ -464,6 +749,56 @@ impl std::fmt::Debug for WhereClauseItem {
     }
 }
 
+impl std::fmt::Debug for LifetimePredicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LifetimePredicate")
+            .field("lifetime", &self.lifetime)
+            .field("bounds", &self.bounds)
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for TyPredicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TyPredicate")
+            .field("for_binder", &self.for_binder)
+            .field("left_side", &self.left_side)
+            .field("bounds", &self.bounds)
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for TraitBound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut list = f.debug_list();
+        for token in quote::quote!(#self) {
+            list.entry(&TokenRef(&token));
+        }
+        list.finish()
+    }
+}
+
+impl std::fmt::Debug for ForBinder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("for").field(&self.params).finish()
+    }
+}
+
+impl std::fmt::Debug for Lifetime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}", self.name)
+    }
+}
+
+impl std::fmt::Debug for Member {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Member::Named(name) => f.write_str(&name.to_string()),
+            Member::Unnamed(index) => write!(f, "{}", index),
+        }
+    }
+}
+
 impl std::fmt::Debug for TyExpr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut list = f.debug_list();
@@ -474,6 +809,18 @@ impl std::fmt::Debug for TyExpr {
     }
 }
 
+impl std::fmt::Debug for DiscriminantValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiscriminantValue::Int(value) => f.debug_tuple("Int").field(value).finish(),
+            DiscriminantValue::Expr(expr) => {
+                let tokens: Vec<_> = expr.iter().map(TokenRef).collect();
+                f.debug_tuple("Expr").field(&tokens).finish()
+            }
+        }
+    }
+}
+
 impl std::fmt::Debug for EnumDiscriminant {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut list = f.debug_list();
@@ -528,15 +875,22 @@ impl ToTokens for StructFields {
     }
 }
 
+// Groups are rebuilt from their parsed contents, so that
+// edits to fields and variants show up in the output.
+
 impl ToTokens for TupleStructFields {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        self.tk_parens.to_tokens(tokens);
+        let mut group = Group::new(Delimiter::Parenthesis, self.fields.to_token_stream());
+        group.set_span(self.tk_parens.span());
+        tokens.append(group);
     }
 }
 
 impl ToTokens for NamedStructFields {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        self.tk_braces.to_tokens(tokens);
+        let mut group = Group::new(Delimiter::Brace, self.fields.to_token_stream());
+        group.set_span(self.tk_braces.span());
+        tokens.append(group);
     }
 }
 
@@ -550,7 +904,10 @@ impl ToTokens for Enum {
         self.name.to_tokens(tokens);
         self.generic_params.to_tokens(tokens);
         self.where_clause.to_tokens(tokens);
-        self.tk_braces.to_tokens(tokens);
+
+        let mut group = Group::new(Delimiter::Brace, self.variants.to_token_stream());
+        group.set_span(self.tk_braces.span());
+        tokens.append(group);
     }
 }
 
@@ -576,11 +933,12 @@ impl ToTokens for Union {
         self.name.to_tokens(tokens);
         self.generic_params.to_tokens(tokens);
         self.where_clause.to_tokens(tokens);
-        self.tk_braces.to_tokens(tokens);
+        self.fields.to_tokens(tokens);
     }
 }
 
-// FIXME
+// Function doesn't store the `fn` keyword, the parameter parentheses,
+// the `->` arrow or the trailing semicolon, so they are created here.
 impl ToTokens for Function {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         for attribute in &self.attributes {
@@ -588,12 +946,24 @@ impl ToTokens for Function {
         }
         self.vis_marker.to_tokens(tokens);
         self.qualifiers.to_tokens(tokens);
+        // The tokens which aren't stored get the span of the function's name.
+        let span = self.name.span();
+        tokens.append(Ident::new("fn", span));
         self.name.to_tokens(tokens);
         self.generic_params.to_tokens(tokens);
-        self.params.to_tokens(tokens);
+        let mut params = Group::new(Delimiter::Parenthesis, self.params.to_token_stream());
+        params.set_span(span);
+        tokens.append(params);
+        if let Some(return_ty) = &self.return_ty {
+            tokens.append(spanned_punct('-', Spacing::Joint, span));
+            tokens.append(spanned_punct('>', Spacing::Alone, span));
+            return_ty.to_tokens(tokens);
+        }
         self.where_clause.to_tokens(tokens);
-        //self.return_ty.to_tokens(tokens);
-        //self.body.to_tokens(tokens);
+        match &self.body {
+            Some(body) => body.to_tokens(tokens),
+            None => tokens.append(spanned_punct(';', Spacing::Alone, span)),
+        }
     }
 }
 
@@ -608,13 +978,13 @@ impl ToTokens for FunctionQualifiers {
     }
 }
 
-// FIXME
 impl ToTokens for FunctionParameter {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         for attribute in &self.attributes {
             attribute.to_tokens(tokens);
         }
         self.name.to_tokens(tokens);
+        tokens.append(spanned_punct(':', Spacing::Alone, self.name.span()));
         self.ty.to_tokens(tokens);
     }
 }
@@ -644,7 +1014,12 @@ impl ToTokens for NamedField {
 impl ToTokens for Attribute {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         tokens.append(self._hashbang.clone());
-        tokens.append(self._braces.clone());
+        let mut group = Group::new(
+            Delimiter::Bracket,
+            self.child_tokens.iter().cloned().collect(),
+        );
+        group.set_span(self._braces.span());
+        tokens.append(group);
     }
 }
 
@@ -665,9 +1040,22 @@ impl ToTokens for GenericParams {
 
 impl ToTokens for GenericParam {
     fn to_tokens(&self, tokens: &mut TokenStream) {
+        for attribute in &self.attributes {
+            attribute.to_tokens(tokens);
+        }
         self._prefix.to_tokens(tokens);
         self.name.to_tokens(tokens);
         self.bound.to_tokens(tokens);
+        self.default.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for GenericDefault {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self._eq.to_tokens(tokens);
+        for token in &self.tokens {
+            tokens.append(token.clone());
+        }
     }
 }
 
@@ -696,6 +1084,35 @@ impl ToTokens for InlineGenericArgs<'_> {
     }
 }
 
+impl ToTokens for ImplGenerics<'_> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let params = match self.0 {
+            Some(params) if !params.params.is_empty() => params,
+            _ => return,
+        };
+
+        tokens.append(Punct::new('<', Spacing::Alone));
+
+        for param in params.params.items() {
+            param._prefix.to_tokens(tokens);
+            tokens.append(param.name.clone());
+            param.bound.to_tokens(tokens);
+            tokens.append(Punct::new(',', Spacing::Alone));
+        }
+
+        tokens.append(Punct::new('>', Spacing::Alone));
+    }
+}
+
+impl ToTokens for TyGenerics<'_> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self.0 {
+            Some(params) if !params.params.is_empty() => params.as_inline_args().to_tokens(tokens),
+            _ => (),
+        }
+    }
+}
+
 impl ToTokens for WhereClause {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         tokens.append(self._where.clone());
@@ -705,10 +1122,67 @@ impl ToTokens for WhereClause {
 
 impl ToTokens for WhereClauseItem {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        for token in &self.left_side {
-            tokens.append(token.clone());
+        match self {
+            WhereClauseItem::Lifetime(predicate) => predicate.to_tokens(tokens),
+            WhereClauseItem::Ty(predicate) => predicate.to_tokens(tokens),
+        }
+    }
+}
+
+impl ToTokens for LifetimePredicate {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.lifetime.to_tokens(tokens);
+        self._colon.to_tokens(tokens);
+        self.bounds.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for TyPredicate {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.for_binder.to_tokens(tokens);
+        self.left_side.to_tokens(tokens);
+        self._colon.to_tokens(tokens);
+        self.bounds.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for ForBinder {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self._for.to_tokens(tokens);
+        self.params.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Lifetime {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self._apostrophe.to_tokens(tokens);
+        self.name.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for TyBound {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            TyBound::Lifetime(lifetime) => lifetime.to_tokens(tokens),
+            TyBound::Trait(trait_bound) => trait_bound.to_tokens(tokens),
+        }
+    }
+}
+
+impl ToTokens for TraitBound {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self._question.to_tokens(tokens);
+        self.for_binder.to_tokens(tokens);
+        self.path.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Member {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Member::Named(name) => name.to_tokens(tokens),
+            Member::Unnamed(index) => Literal::usize_unsuffixed(*index).to_tokens(tokens),
         }
-        self.bound.to_tokens(tokens);
     }
 }
 
@@ -728,6 +1202,29 @@ impl ToTokens for EnumDiscriminant {
     }
 }
 
+impl ToTokens for DiscriminantValue {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            DiscriminantValue::Int(value) if *value < 0 => {
+                tokens.append(Punct::new('-', Spacing::Alone));
+                tokens.append(Literal::u128_unsuffixed(value.unsigned_abs()));
+            }
+            DiscriminantValue::Int(value) => tokens.append(Literal::i128_unsuffixed(*value)),
+            DiscriminantValue::Expr(expr) => {
+                for token in expr {
+                    tokens.append(token.clone());
+                }
+            }
+        }
+    }
+}
+
+fn spanned_punct(ch: char, spacing: Spacing, span: Span) -> Punct {
+    let mut punct = Punct::new(ch, spacing);
+    punct.set_span(span);
+    punct
+}
+
 // --- Default impls ---
 
 impl Default for GenericParams {
@@ -1,13 +1,17 @@
 #![allow(missing_docs)]
 #![allow(unused)]
 
+use crate::punctuated::{Plus, Punctuated};
 pub use crate::types::{
     Attribute, Declaration, Enum, EnumDiscriminant, EnumVariant, GenericBound, GenericParam,
-    GenericParams, NamedField, Struct, StructFields, TupleField, TyExpr, Union, VisMarker,
-    WhereClause, WhereClauseItem,
+    GenericParams, Lifetime, NamedField, NamedStructFields, Struct, StructFields, TupleField,
+    TupleStructFields, TyExpr, Union, VisMarker, WhereClause, WhereClauseItem,
+};
+use crate::types::{
+    Field, Function, ImplGenerics, ImplTraitOptions, InlineGenericArgs, Member, TyGenerics,
 };
-use crate::types::{Function, InlineGenericArgs};
 use proc_macro2::{Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
 
 impl Declaration {
     pub fn generic_params(&self) -> Option<&GenericParams> {
@@ -28,6 +32,24 @@ impl Declaration {
         }
     }
 
+    pub fn attributes(&self) -> &[Attribute] {
+        match self {
+            Declaration::Struct(struct_decl) => &struct_decl.attributes,
+            Declaration::Enum(enum_decl) => &enum_decl.attributes,
+            Declaration::Union(union_decl) => &union_decl.attributes,
+            Declaration::Function(function_decl) => &function_decl.attributes,
+        }
+    }
+
+    pub fn attributes_mut(&mut self) -> &mut Vec<Attribute> {
+        match self {
+            Declaration::Struct(struct_decl) => &mut struct_decl.attributes,
+            Declaration::Enum(enum_decl) => &mut enum_decl.attributes,
+            Declaration::Union(union_decl) => &mut union_decl.attributes,
+            Declaration::Function(function_decl) => &mut function_decl.attributes,
+        }
+    }
+
     pub fn name(&self) -> Ident {
         match self {
             Declaration::Struct(struct_decl) => struct_decl.name.clone(),
@@ -37,6 +59,81 @@ impl Declaration {
         }
     }
 
+    /// Returns the generics and where clause to use when writing an impl block.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let declaration = parse_declaration(quote!(
+    ///     struct Hello<T: Clone = i32>(T) where T: Default;
+    /// ));
+    /// let (impl_generics, ty_generics, where_clause) = declaration.split_for_impl();
+    /// let name = declaration.name();
+    /// let impl_block = quote!(
+    ///     impl #impl_generics MyTrait for #name #ty_generics #where_clause {}
+    /// );
+    /// ```
+    pub fn split_for_impl(&self) -> (ImplGenerics<'_>, TyGenerics<'_>, Option<&WhereClause>) {
+        match self {
+            Declaration::Struct(struct_decl) => struct_decl.split_for_impl(),
+            Declaration::Enum(enum_decl) => enum_decl.split_for_impl(),
+            Declaration::Union(union_decl) => union_decl.split_for_impl(),
+            Declaration::Function(function_decl) => (
+                ImplGenerics(function_decl.generic_params.as_ref()),
+                TyGenerics(function_decl.generic_params.as_ref()),
+                function_decl.where_clause.as_ref(),
+            ),
+        }
+    }
+
+    /// Returns an impl block of `trait_path` for this declaration, containing `body`.
+    ///
+    /// The impl has the declaration's generic params, and its where clause is the
+    /// one returned by `create_derive_where_clause(trait_path)`.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let declaration = parse_declaration(quote!(
+    ///     struct Hello<'a, T: Clone>(&'a T);
+    /// ));
+    /// let impl_block = declaration.impl_trait(
+    ///     quote!(MyTrait),
+    ///     quote!(fn hello() {}),
+    /// );
+    /// // impl<'a, T: Clone> MyTrait for Hello<'a, T> where T: MyTrait { fn hello() {} }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the declaration is a function.
+    pub fn impl_trait(&self, trait_path: TokenStream, body: TokenStream) -> TokenStream {
+        self.impl_trait_with(trait_path, body, &ImplTraitOptions::default())
+    }
+
+    /// Same as [`Declaration::impl_trait`], with additional generic params and
+    /// where clause items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the declaration is a function, or if an extra param has the
+    /// same name as one of the declaration's params.
+    pub fn impl_trait_with(
+        &self,
+        trait_path: TokenStream,
+        body: TokenStream,
+        options: &ImplTraitOptions,
+    ) -> TokenStream {
+        match self {
+            Declaration::Struct(struct_decl) => {
+                struct_decl.impl_trait_with(trait_path, body, options)
+            }
+            Declaration::Enum(enum_decl) => enum_decl.impl_trait_with(trait_path, body, options),
+            Declaration::Union(union_decl) => union_decl.impl_trait_with(trait_path, body, options),
+            Declaration::Function(_) => panic!("cannot implement trait for a function"),
+        }
+    }
+
     pub fn as_struct(&self) -> Option<&Struct> {
         match self {
             Declaration::Struct(struct_decl) => Some(&struct_decl),
@@ -69,51 +166,11 @@ impl Declaration {
 // TODO - use macros to remove copy-pasting
 
 impl Struct {
-    // TODO - document
-    fn field_names(&self) -> impl IntoIterator<Item = String> {
-        match &self.fields {
-            StructFields::Unit => Vec::new(),
-            StructFields::Tuple(tuple_fields) => {
-                let range = 0..tuple_fields.fields.len();
-                range.map(|i| i.to_string()).collect()
-            }
-            StructFields::Named(named_fields) => named_fields
-                .fields
-                .items()
-                .map(|field| field.name.to_string())
-                .collect(),
-        }
-    }
-
-    // TODO - document
-    fn field_tokens(&self) -> impl IntoIterator<Item = TokenTree> {
-        match &self.fields {
-            StructFields::Unit => Vec::new(),
-            StructFields::Tuple(tuple_fields) => {
-                let range = 0..tuple_fields.fields.len();
-                range.map(|i| Literal::usize_unsuffixed(i).into()).collect()
-            }
-            StructFields::Named(named_fields) => named_fields
-                .fields
-                .items()
-                .map(|field| field.name.clone().into())
-                .collect(),
-        }
-    }
-
-    // TODO - document
-    fn field_types(&self) -> impl IntoIterator<Item = &TyExpr> {
-        match &self.fields {
-            StructFields::Unit => Vec::new(),
-            StructFields::Tuple(tuple_fields) => {
-                tuple_fields.fields.items().map(|field| &field.ty).collect()
-            }
-            StructFields::Named(named_fields) => {
-                named_fields.fields.items().map(|field| &field.ty).collect()
-            }
-        }
-    }
-
+    /// Adds a generic parameter. See [`GenericParams::with_param`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type already has a parameter with the same name.
     pub fn with_param(mut self, param: GenericParam) -> Self {
         let params = self.generic_params.take().unwrap_or_default();
         let params = params.with_param(param);
@@ -173,23 +230,81 @@ impl Struct {
         Some(self.generic_params.as_ref()?.as_inline_args())
     }
 
+    /// Returns the generics and where clause to use when writing an impl block.
+    ///
+    /// See [`Declaration::split_for_impl`] for an example.
+    pub fn split_for_impl(&self) -> (ImplGenerics<'_>, TyGenerics<'_>, Option<&WhereClause>) {
+        (
+            ImplGenerics(self.generic_params.as_ref()),
+            TyGenerics(self.generic_params.as_ref()),
+            self.where_clause.as_ref(),
+        )
+    }
+
+    /// Returns an impl block of `trait_path` for this type, containing `body`.
+    ///
+    /// See [`Declaration::impl_trait`] for details.
+    pub fn impl_trait(&self, trait_path: TokenStream, body: TokenStream) -> TokenStream {
+        self.impl_trait_with(trait_path, body, &ImplTraitOptions::default())
+    }
+
+    /// Same as [`Self::impl_trait`], with additional generic params and where clause items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an extra param has the same name as one of the type's params.
+    pub fn impl_trait_with(
+        &self,
+        trait_path: TokenStream,
+        body: TokenStream,
+        options: &ImplTraitOptions,
+    ) -> TokenStream {
+        impl_trait(
+            &self.name,
+            self.generic_params.as_ref(),
+            self.create_derive_where_clause(trait_path.clone()),
+            trait_path,
+            body,
+            options,
+        )
+    }
+
     pub fn create_derive_where_clause(&self, derived_trait: TokenStream) -> WhereClause {
         let mut where_clause = self.where_clause.clone().unwrap_or_default();
 
         for param in self.get_type_params() {
-            let item = WhereClauseItem {
-                left_side: vec![param.name.clone().into()],
-                bound: GenericBound {
-                    _colon: Punct::new(':', Spacing::Alone),
-                    tokens: derived_trait.clone().into_iter().collect(),
-                },
-            };
+            let param_name = &param.name;
+            let item = WhereClauseItem::parse(quote!(#param_name: #derived_trait));
 
             where_clause = where_clause.with_item(item);
         }
 
         where_clause
     }
+
+    /// Creates a where clause bounding the type of each field that mentions a
+    /// type or const parameter by `derived_trait`.
+    ///
+    /// For instance, with `derived_trait = Clone`, `struct Foo<T> { a: Box<T>, b: u32 }`
+    /// gets the where clause `where Box<T>: Clone`. Fields of type `PhantomData<...>`
+    /// and fields with an attribute whose path is `skip_attribute` are ignored.
+    ///
+    /// Unlike [`Self::create_derive_where_clause`], this doesn't bound parameters
+    /// that are only used through `PhantomData`, and handles associated types such
+    /// as `T::Item`.
+    pub fn create_field_bound_where_clause(
+        &self,
+        derived_trait: TokenStream,
+        skip_attribute: Option<&str>,
+    ) -> WhereClause {
+        create_field_bound_where_clause(
+            self.where_clause.as_ref(),
+            self.generic_params.as_ref(),
+            self.fields.iter().map(Field::attributes_and_ty),
+            derived_trait,
+            skip_attribute,
+        )
+    }
 }
 
 impl Enum {
@@ -203,6 +318,11 @@ impl Enum {
         true
     }
 
+    /// Adds a generic parameter. See [`GenericParams::with_param`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type already has a parameter with the same name.
     pub fn with_param(mut self, param: GenericParam) -> Self {
         let params = self.generic_params.take().unwrap_or_default();
         let params = params.with_param(param);
@@ -262,26 +382,85 @@ impl Enum {
         Some(self.generic_params.as_ref()?.as_inline_args())
     }
 
+    /// Returns the generics and where clause to use when writing an impl block.
+    ///
+    /// See [`Declaration::split_for_impl`] for an example.
+    pub fn split_for_impl(&self) -> (ImplGenerics<'_>, TyGenerics<'_>, Option<&WhereClause>) {
+        (
+            ImplGenerics(self.generic_params.as_ref()),
+            TyGenerics(self.generic_params.as_ref()),
+            self.where_clause.as_ref(),
+        )
+    }
+
+    /// Returns an impl block of `trait_path` for this type, containing `body`.
+    ///
+    /// See [`Declaration::impl_trait`] for details.
+    pub fn impl_trait(&self, trait_path: TokenStream, body: TokenStream) -> TokenStream {
+        self.impl_trait_with(trait_path, body, &ImplTraitOptions::default())
+    }
+
+    /// Same as [`Self::impl_trait`], with additional generic params and where clause items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an extra param has the same name as one of the type's params.
+    pub fn impl_trait_with(
+        &self,
+        trait_path: TokenStream,
+        body: TokenStream,
+        options: &ImplTraitOptions,
+    ) -> TokenStream {
+        impl_trait(
+            &self.name,
+            self.generic_params.as_ref(),
+            self.create_derive_where_clause(trait_path.clone()),
+            trait_path,
+            body,
+            options,
+        )
+    }
+
     pub fn create_derive_where_clause(&self, derived_trait: TokenStream) -> WhereClause {
         let mut where_clause = self.where_clause.clone().unwrap_or_default();
 
         for param in self.get_type_params() {
-            let item = WhereClauseItem {
-                left_side: vec![param.name.clone().into()],
-                bound: GenericBound {
-                    _colon: Punct::new(':', Spacing::Alone),
-                    tokens: derived_trait.clone().into_iter().collect(),
-                },
-            };
+            let param_name = &param.name;
+            let item = WhereClauseItem::parse(quote!(#param_name: #derived_trait));
 
             where_clause = where_clause.with_item(item);
         }
 
         where_clause
     }
+
+    /// Creates a where clause bounding the type of each field of each variant
+    /// that mentions a type or const parameter by `derived_trait`.
+    ///
+    /// See [`Struct::create_field_bound_where_clause`] for details.
+    pub fn create_field_bound_where_clause(
+        &self,
+        derived_trait: TokenStream,
+        skip_attribute: Option<&str>,
+    ) -> WhereClause {
+        create_field_bound_where_clause(
+            self.where_clause.as_ref(),
+            self.generic_params.as_ref(),
+            self.variants
+                .items()
+                .flat_map(|variant| variant.contents.iter().map(Field::attributes_and_ty)),
+            derived_trait,
+            skip_attribute,
+        )
+    }
 }
 
 impl Union {
+    /// Adds a generic parameter. See [`GenericParams::with_param`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type already has a parameter with the same name.
     pub fn with_param(mut self, param: GenericParam) -> Self {
         let params = self.generic_params.take().unwrap_or_default();
         let params = params.with_param(param);
@@ -341,23 +520,75 @@ impl Union {
         Some(self.generic_params.as_ref()?.as_inline_args())
     }
 
+    /// Returns the generics and where clause to use when writing an impl block.
+    ///
+    /// See [`Declaration::split_for_impl`] for an example.
+    pub fn split_for_impl(&self) -> (ImplGenerics<'_>, TyGenerics<'_>, Option<&WhereClause>) {
+        (
+            ImplGenerics(self.generic_params.as_ref()),
+            TyGenerics(self.generic_params.as_ref()),
+            self.where_clause.as_ref(),
+        )
+    }
+
+    /// Returns an impl block of `trait_path` for this type, containing `body`.
+    ///
+    /// See [`Declaration::impl_trait`] for details.
+    pub fn impl_trait(&self, trait_path: TokenStream, body: TokenStream) -> TokenStream {
+        self.impl_trait_with(trait_path, body, &ImplTraitOptions::default())
+    }
+
+    /// Same as [`Self::impl_trait`], with additional generic params and where clause items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an extra param has the same name as one of the type's params.
+    pub fn impl_trait_with(
+        &self,
+        trait_path: TokenStream,
+        body: TokenStream,
+        options: &ImplTraitOptions,
+    ) -> TokenStream {
+        impl_trait(
+            &self.name,
+            self.generic_params.as_ref(),
+            self.create_derive_where_clause(trait_path.clone()),
+            trait_path,
+            body,
+            options,
+        )
+    }
+
     pub fn create_derive_where_clause(&self, derived_trait: TokenStream) -> WhereClause {
         let mut where_clause = self.where_clause.clone().unwrap_or_default();
 
         for param in self.get_type_params() {
-            let item = WhereClauseItem {
-                left_side: vec![param.name.clone().into()],
-                bound: GenericBound {
-                    _colon: Punct::new(':', Spacing::Alone),
-                    tokens: derived_trait.clone().into_iter().collect(),
-                },
-            };
+            let param_name = &param.name;
+            let item = WhereClauseItem::parse(quote!(#param_name: #derived_trait));
 
             where_clause = where_clause.with_item(item);
         }
 
         where_clause
     }
+
+    /// Creates a where clause bounding the type of each field that mentions a
+    /// type or const parameter by `derived_trait`.
+    ///
+    /// See [`Struct::create_field_bound_where_clause`] for details.
+    pub fn create_field_bound_where_clause(
+        &self,
+        derived_trait: TokenStream,
+        skip_attribute: Option<&str>,
+    ) -> WhereClause {
+        create_field_bound_where_clause(
+            self.where_clause.as_ref(),
+            self.generic_params.as_ref(),
+            self.fields.iter().map(Field::attributes_and_ty),
+            derived_trait,
+            skip_attribute,
+        )
+    }
 }
 
 impl EnumVariant {
@@ -369,7 +600,7 @@ impl EnumVariant {
     // TODO - document
     pub fn get_single_type(&self) -> Option<&TupleField> {
         match &self.contents {
-            StructFields::Tuple(fields) if fields.fields.len() == 1 => Some(&fields.fields[0].0),
+            StructFields::Tuple(fields) if fields.fields.len() == 1 => Some(&fields.fields[0]),
             StructFields::Tuple(fields) => None,
             StructFields::Unit => None,
             StructFields::Named(_) => None,
@@ -377,73 +608,408 @@ impl EnumVariant {
     }
 }
 
+impl ImplTraitOptions {
+    pub fn with_param(mut self, param: GenericParam) -> Self {
+        self.extra_params.push(param);
+        self
+    }
+
+    pub fn with_where_item(mut self, item: WhereClauseItem) -> Self {
+        self.extra_predicates.push(item);
+        self
+    }
+}
+
+impl StructFields {
+    /// Returns an iterator over the fields, named or not.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, Declaration};
+    /// # use quote::quote;
+    /// let struct_type = parse_declaration(quote!(
+    ///     struct Hello(u8, String);
+    /// ));
+    /// let struct_type = struct_type.as_struct().unwrap();
+    /// let members: Vec<_> = struct_type.fields.iter().map(|field| field.member()).collect();
+    /// # assert_eq!(members.len(), 2);
+    /// // Use with `quote!(#(self.#members),*)`
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = Field<'_>> {
+        let fields: Vec<Field> = match self {
+            StructFields::Unit => Vec::new(),
+            StructFields::Tuple(tuple_fields) => tuple_fields.iter().collect(),
+            StructFields::Named(named_fields) => named_fields.iter().collect(),
+        };
+        fields.into_iter()
+    }
+
+    /// Returns the number of fields.
+    pub fn len(&self) -> usize {
+        match self {
+            StructFields::Unit => 0,
+            StructFields::Tuple(tuple_fields) => tuple_fields.fields.len(),
+            StructFields::Named(named_fields) => named_fields.fields.len(),
+        }
+    }
+
+    /// Returns true if there are no fields, eg `struct Foo;`, `struct Foo();` or `struct Foo {}`.
+    ///
+    /// This is the "fieldless" shape, whatever the delimiters. Use
+    /// [`StructFields::is_unit`] to only match `struct Foo;`.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true for unit structs and variants, eg `struct Foo;`.
+    pub fn is_unit(&self) -> bool {
+        matches!(self, StructFields::Unit)
+    }
+
+    /// Returns true for tuple structs and variants with exactly one field, eg `struct Foo(u32);`.
+    pub fn is_newtype(&self) -> bool {
+        matches!(self, StructFields::Tuple(tuple_fields) if tuple_fields.fields.len() == 1)
+    }
+
+    /// Returns true for tuple structs and variants, eg `struct Foo(u32, u32);`.
+    pub fn is_tuple(&self) -> bool {
+        matches!(self, StructFields::Tuple(_))
+    }
+
+    /// Returns true for structs and variants with named fields, eg `struct Foo { a: u32 }`.
+    pub fn is_named(&self) -> bool {
+        matches!(self, StructFields::Named(_))
+    }
+}
+
+impl TupleStructFields {
+    /// Returns an iterator over the fields.
+    pub fn iter(&self) -> impl Iterator<Item = Field<'_>> {
+        self.fields.items().enumerate().map(|(index, field)| Field {
+            index,
+            name: None,
+            attributes: &field.attributes,
+            vis_marker: field.vis_marker.as_ref(),
+            ty: &field.ty,
+        })
+    }
+}
+
+impl NamedStructFields {
+    /// Returns an iterator over the fields.
+    pub fn iter(&self) -> impl Iterator<Item = Field<'_>> {
+        self.fields.items().enumerate().map(|(index, field)| Field {
+            index,
+            name: Some(&field.name),
+            attributes: &field.attributes,
+            vis_marker: field.vis_marker.as_ref(),
+            ty: &field.ty,
+        })
+    }
+}
+
+impl<'a> Field<'a> {
+    /// Returns the tokens to access this field, eg `foo` in `self.foo`, or `0` in `self.0`.
+    pub fn member(&self) -> Member {
+        match self.name {
+            Some(name) => Member::Named(name.clone()),
+            None => Member::Unnamed(self.index),
+        }
+    }
+
+    fn attributes_and_ty(self) -> (&'a [Attribute], &'a TyExpr) {
+        (self.attributes, self.ty)
+    }
+}
+
 impl GenericParams {
+    /// Adds a parameter, keeping lifetimes before type and const parameters.
+    ///
+    /// Lifetimes are inserted after the existing lifetimes, and other
+    /// parameters are appended at the end of the list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the list already has a parameter with the same name.
     pub fn with_param(mut self, param: GenericParam) -> Self {
+        if self.get_param(&param.lookup_name()).is_some() {
+            panic!(
+                "cannot add generic param: name `{}` is already used",
+                param.lookup_name()
+            );
+        }
+
         if param.is_lifetime() {
-            self.params.insert(0, param, None);
+            let index = self
+                .params
+                .items()
+                .filter(|param| param.is_lifetime())
+                .count();
+            self.params.insert(index, param, None);
         } else {
             self.params.push(param, None);
         }
         self
     }
 
+    /// Adds every parameter of `other` which isn't already in this list.
+    ///
+    /// This is useful when a wrapper type combines the generics of several
+    /// declarations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if both lists have different parameters with the same name,
+    /// eg `T: Clone` and `T: Copy`.
+    pub fn merge(mut self, other: GenericParams) -> Self {
+        for (param, _punct) in other.params.inner {
+            match self.get_param(&param.lookup_name()) {
+                Some(existing_param) if tokens_eq(existing_param, &param) => (),
+                Some(_) => panic!(
+                    "cannot merge generic params: conflicting declarations of `{}`",
+                    param.lookup_name()
+                ),
+                None => self = self.with_param(param),
+            }
+        }
+        self
+    }
+
+    /// Returns the parameter with the given name.
+    ///
+    /// Lifetimes are looked up with their apostrophe, eg `get_param("'a")`.
+    pub fn get_param(&self, name: &str) -> Option<&GenericParam> {
+        self.params
+            .items()
+            .find(|param| param.lookup_name() == name)
+    }
+
+    /// Removes the parameter with the given name, and returns it.
+    ///
+    /// Lifetimes are looked up with their apostrophe, eg `remove_param("'a")`.
+    pub fn remove_param(&mut self, name: &str) -> Option<GenericParam> {
+        let index = self
+            .params
+            .items()
+            .position(|param| param.lookup_name() == name)?;
+        let (param, _punct) = self.params.inner.remove(index);
+        Some(param)
+    }
+
+    /// Returns a lifetime whose name isn't used in this list, eg `'__a`.
+    ///
+    /// To avoid names used elsewhere in a declaration, eg in field types, use
+    /// [`NameGen`](crate::NameGen) instead.
+    pub fn fresh_lifetime(&self) -> GenericParam {
+        let mut name = "__a".to_string();
+        let mut i = 0;
+        while self.get_param(&format!("'{}", name)).is_some() {
+            i += 1;
+            name = format!("__a{}", i);
+        }
+        GenericParam::lifetime(&name)
+    }
+
+    /// Returns a type parameter whose name isn't used in this list, eg `__T0`.
+    ///
+    /// To avoid names used elsewhere in a declaration, eg in field types, use
+    /// [`NameGen`](crate::NameGen) instead.
+    pub fn fresh_ty_param(&self) -> GenericParam {
+        let mut i = 0;
+        while self.get_param(&format!("__T{}", i)).is_some() {
+            i += 1;
+        }
+        GenericParam::ty(&format!("__T{}", i))
+    }
+
     pub fn as_inline_args(&self) -> InlineGenericArgs<'_> {
         InlineGenericArgs(&self)
     }
+
+    /// Moves the inline bounds of lifetime and type parameters into `where_clause`.
+    ///
+    /// For instance, `<'a: 'b, T: Clone, const N: usize>` becomes `<'a, T, const N: usize>`,
+    /// and `'a: 'b, T: Clone` is appended to the where clause. The type of const
+    /// parameters isn't a bound, and is left in place.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, Declaration};
+    /// # use quote::quote;
+    /// # let mut struct_type = match parse_declaration(quote!(struct Hello<T: Clone>(T);)) {
+    /// #     Declaration::Struct(struct_type) => struct_type,
+    /// #     _ => unreachable!(),
+    /// # };
+    /// let where_clause = struct_type.where_clause.get_or_insert_with(Default::default);
+    /// if let Some(generic_params) = &mut struct_type.generic_params {
+    ///     generic_params.hoist_bounds(where_clause);
+    /// }
+    /// ```
+    pub fn hoist_bounds(&mut self, where_clause: &mut WhereClause) {
+        for (param, _punct) in &mut self.params.inner {
+            if param.is_const() {
+                continue;
+            }
+            let bound = match param.bound.take() {
+                Some(bound) => bound,
+                None => continue,
+            };
+
+            let prefix = &param._prefix;
+            let name = &param.name;
+            let item = WhereClauseItem::parse(quote!(#prefix #name #bound));
+            where_clause.items.push(item, None);
+        }
+    }
+
+    /// Moves where clause items bounding a single parameter back into the
+    /// parameter's inline bounds.
+    ///
+    /// This is the inverse of [`GenericParams::hoist_bounds`]. Items with a
+    /// `for<...>` binder or a left side other than a parameter of this list
+    /// (eg `T::Item` or `Vec<T>`) stay in the where clause.
+    pub fn inline_bounds(&mut self, where_clause: &mut WhereClause) {
+        let items = std::mem::take(&mut where_clause.items);
+        let skip_last = items.skip_last;
+
+        for (item, punct) in items.inner {
+            let param = match &item {
+                WhereClauseItem::Lifetime(predicate) => self
+                    .params
+                    .inner
+                    .iter_mut()
+                    .map(|(param, _punct)| param)
+                    .find(|param| param.is_lifetime() && param.name == predicate.lifetime.name),
+                WhereClauseItem::Ty(predicate) if predicate.for_binder.is_none() => {
+                    match predicate.left_side.tokens.as_slice() {
+                        [TokenTree::Ident(ident)] => self
+                            .params
+                            .inner
+                            .iter_mut()
+                            .map(|(param, _punct)| param)
+                            .find(|param| param.is_ty() && &param.name == ident),
+                        _ => None,
+                    }
+                }
+                WhereClauseItem::Ty(_) => None,
+            };
+
+            let param = match param {
+                Some(param) => param,
+                None => {
+                    where_clause.items.push(item, Some(punct));
+                    continue;
+                }
+            };
+
+            let (colon, bound_tokens) = match &item {
+                WhereClauseItem::Lifetime(predicate) => {
+                    (&predicate._colon, predicate.bounds.to_token_stream())
+                }
+                WhereClauseItem::Ty(predicate) => {
+                    (&predicate._colon, predicate.bounds.to_token_stream())
+                }
+            };
+
+            match &mut param.bound {
+                Some(bound) if !bound.tokens.is_empty() => {
+                    if !bound_tokens.is_empty() {
+                        bound.tokens.push(Punct::new('+', Spacing::Alone).into());
+                        bound.tokens.extend(bound_tokens);
+                    }
+                }
+                bound => {
+                    *bound = Some(GenericBound {
+                        _colon: colon.clone(),
+                        tokens: bound_tokens.into_iter().collect(),
+                    });
+                }
+            }
+        }
+
+        where_clause.items.skip_last = skip_last;
+    }
+
+    /// Returns the generics to use after the `impl` keyword and after the type's
+    /// name when writing an impl block.
+    ///
+    /// The where clause is stored in the declaration, not in its generic params;
+    /// see [`Declaration::split_for_impl`] to get all three at once.
+    pub fn split_for_impl(&self) -> (ImplGenerics<'_>, TyGenerics<'_>) {
+        (ImplGenerics(Some(self)), TyGenerics(Some(self)))
+    }
 }
 
 impl GenericParam {
     pub fn lifetime(name: &str) -> Self {
         let lifetime_ident = Ident::new(name, Span::call_site());
         GenericParam {
+            attributes: Vec::new(),
             _prefix: Some(Punct::new('\'', Spacing::Joint).into()),
             name: lifetime_ident,
             bound: None,
+            default: None,
         }
     }
 
     pub fn bounded_lifetime(name: &str, bound: Vec<TokenTree>) -> Self {
         let lifetime_ident = Ident::new(name, Span::call_site());
         GenericParam {
+            attributes: Vec::new(),
             _prefix: Some(Punct::new('\'', Spacing::Alone).into()),
             name: lifetime_ident,
             bound: Some(GenericBound {
                 _colon: Punct::new(':', Spacing::Alone),
                 tokens: bound,
             }),
+            default: None,
         }
     }
 
     pub fn ty(name: &str) -> Self {
         let ty_ident = Ident::new(name, Span::call_site());
         GenericParam {
+            attributes: Vec::new(),
             _prefix: None,
             name: ty_ident,
             bound: None,
+            default: None,
         }
     }
 
     pub fn bounded_ty(name: &str, bound: Vec<TokenTree>) -> Self {
         let ty_ident = Ident::new(name, Span::call_site());
         GenericParam {
+            attributes: Vec::new(),
             _prefix: None,
             name: ty_ident,
             bound: Some(GenericBound {
                 _colon: Punct::new(':', Spacing::Alone),
                 tokens: bound,
             }),
+            default: None,
         }
     }
 
     pub fn const_param(name: &str, ty: Vec<TokenTree>) -> Self {
         let lifetime_ident = Ident::new(name, Span::call_site());
         GenericParam {
+            attributes: Vec::new(),
             _prefix: Some(Ident::new("const", Span::call_site()).into()),
             name: lifetime_ident,
             bound: Some(GenericBound {
                 _colon: Punct::new(':', Spacing::Alone),
                 tokens: ty,
             }),
+            default: None,
+        }
+    }
+
+    // Lifetimes and other parameters live in different namespaces,
+    // so lifetimes are looked up with their apostrophe.
+    fn lookup_name(&self) -> String {
+        if self.is_lifetime() {
+            format!("'{}", self.name)
+        } else {
+            self.name.to_string()
         }
     }
 
@@ -478,30 +1044,328 @@ impl WhereClause {
         self.items.push(item, None);
         self
     }
+
+    /// Merges items with the same left side into a single item.
+    ///
+    /// For instance, `where T: A, U: C, T: B` becomes `where T: A + B, U: C`.
+    /// Left sides are compared token by token, regardless of spans and spacing.
+    pub fn merge_items(&mut self) {
+        let items = std::mem::take(&mut self.items);
+
+        let mut merged_items: Vec<WhereClauseItem> = Vec::new();
+        for (item, _punct) in items.inner {
+            match merged_items
+                .iter_mut()
+                .find(|merged_item| merged_item.has_same_left_side(&item))
+            {
+                Some(merged_item) => merged_item.append_bounds(item),
+                None => merged_items.push(item),
+            }
+        }
+
+        for item in merged_items {
+            self.items.push(item, None);
+        }
+    }
+
+    /// Removes duplicate items, and duplicate bounds within each item.
+    ///
+    /// For instance, `where T: A + A, T: A` becomes `where T: A`.
+    /// Items and bounds are compared token by token, regardless of spans and spacing.
+    pub fn dedup_items(&mut self) {
+        let items = std::mem::take(&mut self.items);
+
+        let mut unique_items: Vec<WhereClauseItem> = Vec::new();
+        for (mut item, _punct) in items.inner {
+            item.dedup_bounds();
+            if !unique_items
+                .iter()
+                .any(|unique_item| tokens_eq(unique_item, &item))
+            {
+                unique_items.push(item);
+            }
+        }
+
+        for item in unique_items {
+            self.items.push(item, None);
+        }
+    }
 }
 
 impl WhereClauseItem {
     pub fn parse(tokens: TokenStream) -> Self {
         let mut tokens = tokens.into_iter().peekable();
+        let item = crate::parse::consume_where_clause_item(&mut tokens);
+
+        if let Some(token) = tokens.next() {
+            panic!(
+                "cannot parse where clause item: unexpected token {:?}",
+                token
+            );
+        }
+
+        item
+    }
+
+    pub fn is_lifetime(&self) -> bool {
+        matches!(self, WhereClauseItem::Lifetime(_))
+    }
 
-        let left_side = crate::parse::consume_stuff_until(&mut tokens, |token| match token {
-            TokenTree::Punct(punct) if punct.as_char() == ':' => true,
+    pub fn is_ty(&self) -> bool {
+        matches!(self, WhereClauseItem::Ty(_))
+    }
+
+    fn has_same_left_side(&self, other: &WhereClauseItem) -> bool {
+        match (self, other) {
+            (WhereClauseItem::Lifetime(predicate), WhereClauseItem::Lifetime(other)) => {
+                tokens_eq(&predicate.lifetime, &other.lifetime)
+            }
+            (WhereClauseItem::Ty(predicate), WhereClauseItem::Ty(other)) => {
+                tokens_eq(&predicate.for_binder, &other.for_binder)
+                    && tokens_eq(&predicate.left_side, &other.left_side)
+            }
             _ => false,
-        });
+        }
+    }
+
+    // Panics if the two items don't bound the same kind of thing.
+    fn append_bounds(&mut self, other: WhereClauseItem) {
+        match (self, other) {
+            (WhereClauseItem::Lifetime(predicate), WhereClauseItem::Lifetime(other)) => {
+                let bounds = std::mem::take(&mut predicate.bounds);
+                predicate.bounds = bounds.into_iter().chain(other.bounds).collect();
+            }
+            (WhereClauseItem::Ty(predicate), WhereClauseItem::Ty(other)) => {
+                let bounds = std::mem::take(&mut predicate.bounds);
+                predicate.bounds = bounds.into_iter().chain(other.bounds).collect();
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn dedup_bounds(&mut self) {
+        fn dedup<T: ToTokens>(bounds: Punctuated<T, Plus>) -> Punctuated<T, Plus> {
+            let mut unique_bounds: Vec<T> = Vec::new();
+            for (bound, _punct) in bounds.inner {
+                if !unique_bounds
+                    .iter()
+                    .any(|unique_bound| tokens_eq(unique_bound, &bound))
+                {
+                    unique_bounds.push(bound);
+                }
+            }
+            unique_bounds.into_iter().collect()
+        }
+
+        match self {
+            WhereClauseItem::Lifetime(predicate) => {
+                predicate.bounds = dedup(std::mem::take(&mut predicate.bounds));
+            }
+            WhereClauseItem::Ty(predicate) => {
+                predicate.bounds = dedup(std::mem::take(&mut predicate.bounds));
+            }
+        }
+    }
+}
+
+impl Attribute {
+    /// Returns true if the attribute's path is `path`.
+    ///
+    /// For instance, `#[rustfmt::skip]` has the path `"rustfmt::skip"`, and
+    /// `#[serde(rename = "foo")]` has the path `"serde"`.
+    pub fn has_path(&self, path: &str) -> bool {
+        let mut attribute_path = String::new();
+        for token in &self.child_tokens {
+            match token {
+                TokenTree::Ident(ident) => attribute_path += &ident.to_string(),
+                TokenTree::Punct(punct) if punct.as_char() == ':' => attribute_path.push(':'),
+                _ => break,
+            }
+        }
+
+        let path: String = path.chars().filter(|c| !c.is_whitespace()).collect();
+        attribute_path == path
+    }
+}
+
+impl TyExpr {
+    /// Returns true if the type is `PhantomData<...>`, with or without a path prefix.
+    pub fn is_phantom_data(&self) -> bool {
+        let mut last_ident = None;
+        for token in &self.tokens {
+            match token {
+                TokenTree::Ident(ident) => last_ident = Some(ident),
+                TokenTree::Punct(punct) if punct.as_char() == '<' => break,
+                _ => (),
+            }
+        }
+        matches!(last_ident, Some(ident) if ident == "PhantomData")
+    }
+
+    /// Returns true if any of the given names appears in the type, other than
+    /// as a path segment (eg `a::T` or `<X as Tr>::T`).
+    ///
+    /// This is useful to find the fields whose type uses a generic parameter,
+    /// eg in a [`Visit`](crate::visit::Visit) implementation.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, visit::Visit, NamedField};
+    /// # use quote::{format_ident, quote};
+    /// struct MentionsT(Vec<String>);
+    ///
+    /// impl<'ast> Visit<'ast> for MentionsT {
+    ///     fn visit_named_field(&mut self, node: &'ast NamedField) {
+    ///         if node.ty.mentions_any(&[&format_ident!("T")]) {
+    ///             self.0.push(node.name.to_string());
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let declaration = parse_declaration(quote!(
+    ///     struct Foo<T: Iterator> {
+    ///         a: Vec<T>,
+    ///         b: u8,
+    ///         c: std::vec::T,
+    ///         d: T::Item,
+    ///     }
+    /// ));
+    /// let mut visitor = MentionsT(Vec::new());
+    /// visitor.visit_declaration(&declaration);
+    /// assert_eq!(visitor.0, ["a", "d"]);
+    /// ```
+    pub fn mentions_any(&self, names: &[&Ident]) -> bool {
+        fn mentions_any(tokens: TokenStream, names: &[&Ident]) -> bool {
+            let mut prev_token_is_colon = false;
+            for token in tokens {
+                match &token {
+                    TokenTree::Group(group) if mentions_any(group.stream(), names) => {
+                        return true;
+                    }
+                    TokenTree::Ident(ident) if !prev_token_is_colon && names.contains(&ident) => {
+                        return true;
+                    }
+                    _ => (),
+                }
+                prev_token_is_colon =
+                    matches!(&token, TokenTree::Punct(punct) if punct.as_char() == ':');
+            }
+            false
+        }
+
+        mentions_any(self.to_token_stream(), names)
+    }
+}
+
+impl Lifetime {
+    pub fn new(name: &str) -> Self {
+        Lifetime {
+            _apostrophe: Punct::new('\'', Spacing::Joint),
+            name: Ident::new(name, Span::call_site()),
+        }
+    }
+}
+
+fn impl_trait(
+    name: &Ident,
+    generic_params: Option<&GenericParams>,
+    mut where_clause: WhereClause,
+    trait_path: TokenStream,
+    body: TokenStream,
+    options: &ImplTraitOptions,
+) -> TokenStream {
+    let mut impl_params = generic_params.cloned().unwrap_or_default();
+    for param in &options.extra_params {
+        impl_params = impl_params.with_param(param.clone());
+    }
+    for item in &options.extra_predicates {
+        where_clause = where_clause.with_item(item.clone());
+    }
+
+    let impl_generics = ImplGenerics(Some(&impl_params));
+    let ty_generics = TyGenerics(generic_params);
+    let where_clause = if where_clause.items.is_empty() {
+        None
+    } else {
+        Some(where_clause)
+    };
+
+    quote! {
+        impl #impl_generics #trait_path for #name #ty_generics #where_clause {
+            #body
+        }
+    }
+}
 
-        let colon = match tokens.next().unwrap() {
-            TokenTree::Punct(punct) if punct.as_char() == ':' => punct.clone(),
-            _ => panic!("cannot parse type"),
+fn create_field_bound_where_clause<'a>(
+    where_clause: Option<&WhereClause>,
+    generic_params: Option<&GenericParams>,
+    fields: impl IntoIterator<Item = (&'a [Attribute], &'a TyExpr)>,
+    derived_trait: TokenStream,
+    skip_attribute: Option<&str>,
+) -> WhereClause {
+    let mut where_clause = where_clause.cloned().unwrap_or_default();
+
+    let param_names: Vec<&Ident> = match generic_params {
+        Some(generic_params) => generic_params
+            .params
+            .items()
+            .filter(|param| !param.is_lifetime())
+            .map(|param| &param.name)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    for (attributes, ty) in fields {
+        let skipped = match skip_attribute {
+            Some(path) => attributes.iter().any(|attribute| attribute.has_path(path)),
+            None => false,
         };
+        if skipped || ty.is_phantom_data() || !ty.mentions_any(&param_names) {
+            continue;
+        }
+
+        let item = WhereClauseItem::parse(quote!(#ty: #derived_trait));
+        if !where_clause
+            .items
+            .items()
+            .any(|existing_item| tokens_eq(existing_item, &item))
+        {
+            where_clause = where_clause.with_item(item);
+        }
+    }
+
+    where_clause
+}
 
-        let bound_tokens = tokens.collect();
+// --- Token utils ---
 
-        WhereClauseItem {
-            left_side,
-            bound: GenericBound {
-                _colon: colon,
-                tokens: bound_tokens,
-            },
+// Compares the tokens of two values, regardless of spans and spacing.
+pub(crate) fn tokens_eq(left: &impl ToTokens, right: &impl ToTokens) -> bool {
+    token_streams_eq(left.to_token_stream(), right.to_token_stream())
+}
+
+fn token_streams_eq(left: TokenStream, right: TokenStream) -> bool {
+    let mut left = left.into_iter();
+    let mut right = right.into_iter();
+    loop {
+        match (left.next(), right.next()) {
+            (None, None) => return true,
+            (Some(left), Some(right)) if token_trees_eq(&left, &right) => (),
+            _ => return false,
+        }
+    }
+}
+
+fn token_trees_eq(left: &TokenTree, right: &TokenTree) -> bool {
+    match (left, right) {
+        (TokenTree::Group(left), TokenTree::Group(right)) => {
+            left.delimiter() == right.delimiter() && token_streams_eq(left.stream(), right.stream())
+        }
+        (TokenTree::Ident(left), TokenTree::Ident(right)) => left == right,
+        (TokenTree::Punct(left), TokenTree::Punct(right)) => left.as_char() == right.as_char(),
+        (TokenTree::Literal(left), TokenTree::Literal(right)) => {
+            left.to_string() == right.to_string()
         }
+        _ => false,
     }
 }
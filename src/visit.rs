@@ -0,0 +1,422 @@
+#![allow(missing_docs)]
+
+//! Read-only traversal of declarations.
+//!
+//! Each method of [`Visit`] visits one kind of node, and by default calls the
+//! matching `walk_*` function, which visits the node's children. Implementors
+//! override the methods for the nodes they care about, and call the `walk_*`
+//! function if they still want to visit the children.
+//!
+//! ```
+//! # use venial::{parse_declaration, visit::{self, Visit}, NamedField};
+//! # use quote::quote;
+//! struct FieldNames(Vec<String>);
+//!
+//! impl<'ast> Visit<'ast> for FieldNames {
+//!     fn visit_named_field(&mut self, field: &'ast NamedField) {
+//!         self.0.push(field.name.to_string());
+//!         visit::walk_named_field(self, field);
+//!     }
+//! }
+//!
+//! let declaration = parse_declaration(quote!(
+//!     enum Shape {
+//!         Square { side: f32 },
+//!         Rectangle { width: f32, height: f32 },
+//!     }
+//! ));
+//! let mut field_names = FieldNames(Vec::new());
+//! field_names.visit_declaration(&declaration);
+//! assert_eq!(field_names.0, ["side", "width", "height"]);
+//! ```
+
+use crate::types::{
+    Attribute, Declaration, Enum, EnumDiscriminant, EnumVariant, ForBinder, Function,
+    FunctionParameter, FunctionQualifiers, GenericBound, GenericDefault, GenericParam,
+    GenericParams, Lifetime, LifetimePredicate, NamedField, Struct, StructFields, TraitBound,
+    TupleField, TyBound, TyExpr, TyPredicate, Union, VisMarker, WhereClause, WhereClauseItem,
+};
+use proc_macro2::{Spacing, TokenTree};
+
+/// Visits the nodes of a declaration by shared reference.
+///
+/// See the [module documentation](self).
+///
+/// Lifetime parameters, and the lifetimes inside type expressions, bounds and
+/// defaults, aren't stored as [`Lifetime`] nodes. They are passed to
+/// [`visit_lifetime`](Visit::visit_lifetime) as temporary values, which is why
+/// it doesn't take an `&'ast` reference.
+pub trait Visit<'ast> {
+    fn visit_declaration(&mut self, node: &'ast Declaration) {
+        walk_declaration(self, node);
+    }
+
+    fn visit_struct(&mut self, node: &'ast Struct) {
+        walk_struct(self, node);
+    }
+
+    fn visit_enum(&mut self, node: &'ast Enum) {
+        walk_enum(self, node);
+    }
+
+    fn visit_union(&mut self, node: &'ast Union) {
+        walk_union(self, node);
+    }
+
+    fn visit_function(&mut self, node: &'ast Function) {
+        walk_function(self, node);
+    }
+
+    fn visit_struct_fields(&mut self, node: &'ast StructFields) {
+        walk_struct_fields(self, node);
+    }
+
+    fn visit_named_field(&mut self, node: &'ast NamedField) {
+        walk_named_field(self, node);
+    }
+
+    fn visit_tuple_field(&mut self, node: &'ast TupleField) {
+        walk_tuple_field(self, node);
+    }
+
+    fn visit_enum_variant(&mut self, node: &'ast EnumVariant) {
+        walk_enum_variant(self, node);
+    }
+
+    fn visit_enum_discriminant(&mut self, _node: &'ast EnumDiscriminant) {}
+
+    fn visit_function_qualifiers(&mut self, _node: &'ast FunctionQualifiers) {}
+
+    fn visit_function_parameter(&mut self, node: &'ast FunctionParameter) {
+        walk_function_parameter(self, node);
+    }
+
+    fn visit_attribute(&mut self, _node: &'ast Attribute) {}
+
+    fn visit_vis_marker(&mut self, _node: &'ast VisMarker) {}
+
+    fn visit_generic_params(&mut self, node: &'ast GenericParams) {
+        walk_generic_params(self, node);
+    }
+
+    fn visit_generic_param(&mut self, node: &'ast GenericParam) {
+        walk_generic_param(self, node);
+    }
+
+    fn visit_generic_bound(&mut self, node: &'ast GenericBound) {
+        walk_generic_bound(self, node);
+    }
+
+    fn visit_generic_default(&mut self, node: &'ast GenericDefault) {
+        walk_generic_default(self, node);
+    }
+
+    fn visit_where_clause(&mut self, node: &'ast WhereClause) {
+        walk_where_clause(self, node);
+    }
+
+    fn visit_where_clause_item(&mut self, node: &'ast WhereClauseItem) {
+        walk_where_clause_item(self, node);
+    }
+
+    fn visit_lifetime_predicate(&mut self, node: &'ast LifetimePredicate) {
+        walk_lifetime_predicate(self, node);
+    }
+
+    fn visit_ty_predicate(&mut self, node: &'ast TyPredicate) {
+        walk_ty_predicate(self, node);
+    }
+
+    fn visit_ty_bound(&mut self, node: &'ast TyBound) {
+        walk_ty_bound(self, node);
+    }
+
+    fn visit_trait_bound(&mut self, node: &'ast TraitBound) {
+        walk_trait_bound(self, node);
+    }
+
+    fn visit_for_binder(&mut self, node: &'ast ForBinder) {
+        walk_for_binder(self, node);
+    }
+
+    fn visit_lifetime(&mut self, _node: &Lifetime) {}
+
+    fn visit_ty_expr(&mut self, node: &'ast TyExpr) {
+        walk_ty_expr(self, node);
+    }
+}
+
+pub fn walk_declaration<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast Declaration) {
+    match node {
+        Declaration::Struct(struct_decl) => visitor.visit_struct(struct_decl),
+        Declaration::Enum(enum_decl) => visitor.visit_enum(enum_decl),
+        Declaration::Union(union_decl) => visitor.visit_union(union_decl),
+        Declaration::Function(function_decl) => visitor.visit_function(function_decl),
+    }
+}
+
+pub fn walk_struct<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast Struct) {
+    for attribute in &node.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    if let Some(vis_marker) = &node.vis_marker {
+        visitor.visit_vis_marker(vis_marker);
+    }
+    if let Some(generic_params) = &node.generic_params {
+        visitor.visit_generic_params(generic_params);
+    }
+    if let Some(where_clause) = &node.where_clause {
+        visitor.visit_where_clause(where_clause);
+    }
+    visitor.visit_struct_fields(&node.fields);
+}
+
+pub fn walk_enum<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast Enum) {
+    for attribute in &node.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    if let Some(vis_marker) = &node.vis_marker {
+        visitor.visit_vis_marker(vis_marker);
+    }
+    if let Some(generic_params) = &node.generic_params {
+        visitor.visit_generic_params(generic_params);
+    }
+    if let Some(where_clause) = &node.where_clause {
+        visitor.visit_where_clause(where_clause);
+    }
+    for variant in &node.variants {
+        visitor.visit_enum_variant(variant);
+    }
+}
+
+pub fn walk_union<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast Union) {
+    for attribute in &node.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    if let Some(vis_marker) = &node.vis_marker {
+        visitor.visit_vis_marker(vis_marker);
+    }
+    if let Some(generic_params) = &node.generic_params {
+        visitor.visit_generic_params(generic_params);
+    }
+    if let Some(where_clause) = &node.where_clause {
+        visitor.visit_where_clause(where_clause);
+    }
+    for field in &node.fields.fields {
+        visitor.visit_named_field(field);
+    }
+}
+
+pub fn walk_function<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast Function) {
+    for attribute in &node.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    if let Some(vis_marker) = &node.vis_marker {
+        visitor.visit_vis_marker(vis_marker);
+    }
+    visitor.visit_function_qualifiers(&node.qualifiers);
+    if let Some(generic_params) = &node.generic_params {
+        visitor.visit_generic_params(generic_params);
+    }
+    for param in &node.params {
+        visitor.visit_function_parameter(param);
+    }
+    if let Some(return_ty) = &node.return_ty {
+        visitor.visit_ty_expr(return_ty);
+    }
+    if let Some(where_clause) = &node.where_clause {
+        visitor.visit_where_clause(where_clause);
+    }
+}
+
+pub fn walk_struct_fields<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast StructFields,
+) {
+    match node {
+        StructFields::Unit => {}
+        StructFields::Tuple(tuple_fields) => {
+            for field in &tuple_fields.fields {
+                visitor.visit_tuple_field(field);
+            }
+        }
+        StructFields::Named(named_fields) => {
+            for field in &named_fields.fields {
+                visitor.visit_named_field(field);
+            }
+        }
+    }
+}
+
+pub fn walk_named_field<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast NamedField) {
+    for attribute in &node.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    if let Some(vis_marker) = &node.vis_marker {
+        visitor.visit_vis_marker(vis_marker);
+    }
+    visitor.visit_ty_expr(&node.ty);
+}
+
+pub fn walk_tuple_field<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast TupleField) {
+    for attribute in &node.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    if let Some(vis_marker) = &node.vis_marker {
+        visitor.visit_vis_marker(vis_marker);
+    }
+    visitor.visit_ty_expr(&node.ty);
+}
+
+pub fn walk_enum_variant<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast EnumVariant) {
+    for attribute in &node.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    if let Some(vis_marker) = &node.vis_marker {
+        visitor.visit_vis_marker(vis_marker);
+    }
+    visitor.visit_struct_fields(&node.contents);
+    if let Some(discriminant) = &node.discriminant {
+        visitor.visit_enum_discriminant(discriminant);
+    }
+}
+
+pub fn walk_function_parameter<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast FunctionParameter,
+) {
+    for attribute in &node.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    visitor.visit_ty_expr(&node.ty);
+}
+
+pub fn walk_generic_params<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast GenericParams,
+) {
+    for param in &node.params {
+        visitor.visit_generic_param(param);
+    }
+}
+
+pub fn walk_generic_param<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast GenericParam,
+) {
+    for attribute in &node.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    // Only lifetime parameters have a punctuation prefix.
+    if let Some(TokenTree::Punct(apostrophe)) = &node._prefix {
+        visitor.visit_lifetime(&Lifetime {
+            _apostrophe: apostrophe.clone(),
+            name: node.name.clone(),
+        });
+    }
+    if let Some(bound) = &node.bound {
+        visitor.visit_generic_bound(bound);
+    }
+    if let Some(default) = &node.default {
+        visitor.visit_generic_default(default);
+    }
+}
+
+pub fn walk_generic_bound<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast GenericBound,
+) {
+    visit_lifetimes_in(visitor, node.tokens.iter().cloned());
+}
+
+pub fn walk_generic_default<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast GenericDefault,
+) {
+    visit_lifetimes_in(visitor, node.tokens.iter().cloned());
+}
+
+pub fn walk_where_clause<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast WhereClause) {
+    for item in &node.items {
+        visitor.visit_where_clause_item(item);
+    }
+}
+
+pub fn walk_where_clause_item<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast WhereClauseItem,
+) {
+    match node {
+        WhereClauseItem::Lifetime(predicate) => visitor.visit_lifetime_predicate(predicate),
+        WhereClauseItem::Ty(predicate) => visitor.visit_ty_predicate(predicate),
+    }
+}
+
+pub fn walk_lifetime_predicate<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast LifetimePredicate,
+) {
+    visitor.visit_lifetime(&node.lifetime);
+    for bound in &node.bounds {
+        visitor.visit_lifetime(bound);
+    }
+}
+
+pub fn walk_ty_predicate<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast TyPredicate) {
+    if let Some(for_binder) = &node.for_binder {
+        visitor.visit_for_binder(for_binder);
+    }
+    visitor.visit_ty_expr(&node.left_side);
+    for bound in &node.bounds {
+        visitor.visit_ty_bound(bound);
+    }
+}
+
+pub fn walk_ty_bound<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast TyBound) {
+    match node {
+        TyBound::Lifetime(lifetime) => visitor.visit_lifetime(lifetime),
+        TyBound::Trait(trait_bound) => visitor.visit_trait_bound(trait_bound),
+    }
+}
+
+pub fn walk_trait_bound<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast TraitBound) {
+    if let Some(for_binder) = &node.for_binder {
+        visitor.visit_for_binder(for_binder);
+    }
+    visitor.visit_ty_expr(&node.path);
+}
+
+pub fn walk_for_binder<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast ForBinder) {
+    visitor.visit_generic_params(&node.params);
+}
+
+pub fn walk_ty_expr<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, node: &'ast TyExpr) {
+    visit_lifetimes_in(visitor, node.tokens.iter().cloned());
+}
+
+// Calls `visit_lifetime` for each `'name` in the tokens, including the ones
+// inside groups.
+fn visit_lifetimes_in<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    tokens: impl IntoIterator<Item = TokenTree>,
+) {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Group(group) => visit_lifetimes_in(visitor, group.stream()),
+            TokenTree::Punct(apostrophe)
+                if apostrophe.as_char() == '\'' && apostrophe.spacing() == Spacing::Joint =>
+            {
+                if let Some(TokenTree::Ident(name)) = tokens.peek() {
+                    let lifetime = Lifetime {
+                        _apostrophe: apostrophe,
+                        name: name.clone(),
+                    };
+                    tokens.next();
+                    visitor.visit_lifetime(&lifetime);
+                }
+            }
+            _ => (),
+        }
+    }
+}
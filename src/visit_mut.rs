@@ -0,0 +1,349 @@
+#![allow(missing_docs)]
+
+//! In-place transformation of declarations.
+//!
+//! [`VisitMut`] works like [`Visit`](crate::visit::Visit), with mutable
+//! references. Edits are reflected when the declaration is converted back to
+//! tokens.
+//!
+//! ```
+//! # use venial::{parse_declaration, visit_mut::{self, VisitMut}, TyExpr};
+//! # use quote::quote;
+//! struct WrapInOption;
+//!
+//! impl VisitMut for WrapInOption {
+//!     fn visit_ty_expr_mut(&mut self, ty: &mut TyExpr) {
+//!         ty.tokens = quote!(Option<#ty>).into_iter().collect();
+//!     }
+//! }
+//!
+//! let mut declaration = parse_declaration(quote!(
+//!     struct Hello {
+//!         a: u8,
+//!         b: String,
+//!     }
+//! ));
+//! WrapInOption.visit_declaration_mut(&mut declaration);
+//! assert_eq!(
+//!     quote!(#declaration).to_string(),
+//!     quote!(struct Hello { a: Option<u8>, b: Option<String>, }).to_string(),
+//! );
+//! ```
+
+use crate::types::{
+    Attribute, Declaration, Enum, EnumDiscriminant, EnumVariant, ForBinder, Function,
+    FunctionParameter, FunctionQualifiers, GenericBound, GenericDefault, GenericParam,
+    GenericParams, Lifetime, LifetimePredicate, NamedField, Struct, StructFields, TraitBound,
+    TupleField, TyBound, TyExpr, TyPredicate, Union, VisMarker, WhereClause, WhereClauseItem,
+};
+
+/// Visits the nodes of a declaration by mutable reference, to edit them in place.
+///
+/// See the [module documentation](self).
+pub trait VisitMut {
+    fn visit_declaration_mut(&mut self, node: &mut Declaration) {
+        walk_declaration_mut(self, node);
+    }
+
+    fn visit_struct_mut(&mut self, node: &mut Struct) {
+        walk_struct_mut(self, node);
+    }
+
+    fn visit_enum_mut(&mut self, node: &mut Enum) {
+        walk_enum_mut(self, node);
+    }
+
+    fn visit_union_mut(&mut self, node: &mut Union) {
+        walk_union_mut(self, node);
+    }
+
+    fn visit_function_mut(&mut self, node: &mut Function) {
+        walk_function_mut(self, node);
+    }
+
+    fn visit_struct_fields_mut(&mut self, node: &mut StructFields) {
+        walk_struct_fields_mut(self, node);
+    }
+
+    fn visit_named_field_mut(&mut self, node: &mut NamedField) {
+        walk_named_field_mut(self, node);
+    }
+
+    fn visit_tuple_field_mut(&mut self, node: &mut TupleField) {
+        walk_tuple_field_mut(self, node);
+    }
+
+    fn visit_enum_variant_mut(&mut self, node: &mut EnumVariant) {
+        walk_enum_variant_mut(self, node);
+    }
+
+    fn visit_enum_discriminant_mut(&mut self, _node: &mut EnumDiscriminant) {}
+
+    fn visit_function_qualifiers_mut(&mut self, _node: &mut FunctionQualifiers) {}
+
+    fn visit_function_parameter_mut(&mut self, node: &mut FunctionParameter) {
+        walk_function_parameter_mut(self, node);
+    }
+
+    fn visit_attribute_mut(&mut self, _node: &mut Attribute) {}
+
+    fn visit_vis_marker_mut(&mut self, _node: &mut VisMarker) {}
+
+    fn visit_generic_params_mut(&mut self, node: &mut GenericParams) {
+        walk_generic_params_mut(self, node);
+    }
+
+    fn visit_generic_param_mut(&mut self, node: &mut GenericParam) {
+        walk_generic_param_mut(self, node);
+    }
+
+    fn visit_generic_bound_mut(&mut self, _node: &mut GenericBound) {}
+
+    fn visit_generic_default_mut(&mut self, _node: &mut GenericDefault) {}
+
+    fn visit_where_clause_mut(&mut self, node: &mut WhereClause) {
+        walk_where_clause_mut(self, node);
+    }
+
+    fn visit_where_clause_item_mut(&mut self, node: &mut WhereClauseItem) {
+        walk_where_clause_item_mut(self, node);
+    }
+
+    fn visit_lifetime_predicate_mut(&mut self, node: &mut LifetimePredicate) {
+        walk_lifetime_predicate_mut(self, node);
+    }
+
+    fn visit_ty_predicate_mut(&mut self, node: &mut TyPredicate) {
+        walk_ty_predicate_mut(self, node);
+    }
+
+    fn visit_ty_bound_mut(&mut self, node: &mut TyBound) {
+        walk_ty_bound_mut(self, node);
+    }
+
+    fn visit_trait_bound_mut(&mut self, node: &mut TraitBound) {
+        walk_trait_bound_mut(self, node);
+    }
+
+    fn visit_for_binder_mut(&mut self, node: &mut ForBinder) {
+        walk_for_binder_mut(self, node);
+    }
+
+    fn visit_lifetime_mut(&mut self, _node: &mut Lifetime) {}
+
+    fn visit_ty_expr_mut(&mut self, _node: &mut TyExpr) {}
+}
+
+pub fn walk_declaration_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Declaration) {
+    match node {
+        Declaration::Struct(struct_decl) => visitor.visit_struct_mut(struct_decl),
+        Declaration::Enum(enum_decl) => visitor.visit_enum_mut(enum_decl),
+        Declaration::Union(union_decl) => visitor.visit_union_mut(union_decl),
+        Declaration::Function(function_decl) => visitor.visit_function_mut(function_decl),
+    }
+}
+
+pub fn walk_struct_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Struct) {
+    for attribute in &mut node.attributes {
+        visitor.visit_attribute_mut(attribute);
+    }
+    if let Some(vis_marker) = &mut node.vis_marker {
+        visitor.visit_vis_marker_mut(vis_marker);
+    }
+    if let Some(generic_params) = &mut node.generic_params {
+        visitor.visit_generic_params_mut(generic_params);
+    }
+    if let Some(where_clause) = &mut node.where_clause {
+        visitor.visit_where_clause_mut(where_clause);
+    }
+    visitor.visit_struct_fields_mut(&mut node.fields);
+}
+
+pub fn walk_enum_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Enum) {
+    for attribute in &mut node.attributes {
+        visitor.visit_attribute_mut(attribute);
+    }
+    if let Some(vis_marker) = &mut node.vis_marker {
+        visitor.visit_vis_marker_mut(vis_marker);
+    }
+    if let Some(generic_params) = &mut node.generic_params {
+        visitor.visit_generic_params_mut(generic_params);
+    }
+    if let Some(where_clause) = &mut node.where_clause {
+        visitor.visit_where_clause_mut(where_clause);
+    }
+    for variant in &mut node.variants {
+        visitor.visit_enum_variant_mut(variant);
+    }
+}
+
+pub fn walk_union_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Union) {
+    for attribute in &mut node.attributes {
+        visitor.visit_attribute_mut(attribute);
+    }
+    if let Some(vis_marker) = &mut node.vis_marker {
+        visitor.visit_vis_marker_mut(vis_marker);
+    }
+    if let Some(generic_params) = &mut node.generic_params {
+        visitor.visit_generic_params_mut(generic_params);
+    }
+    if let Some(where_clause) = &mut node.where_clause {
+        visitor.visit_where_clause_mut(where_clause);
+    }
+    for field in &mut node.fields.fields {
+        visitor.visit_named_field_mut(field);
+    }
+}
+
+pub fn walk_function_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Function) {
+    for attribute in &mut node.attributes {
+        visitor.visit_attribute_mut(attribute);
+    }
+    if let Some(vis_marker) = &mut node.vis_marker {
+        visitor.visit_vis_marker_mut(vis_marker);
+    }
+    visitor.visit_function_qualifiers_mut(&mut node.qualifiers);
+    if let Some(generic_params) = &mut node.generic_params {
+        visitor.visit_generic_params_mut(generic_params);
+    }
+    for param in &mut node.params {
+        visitor.visit_function_parameter_mut(param);
+    }
+    if let Some(return_ty) = &mut node.return_ty {
+        visitor.visit_ty_expr_mut(return_ty);
+    }
+    if let Some(where_clause) = &mut node.where_clause {
+        visitor.visit_where_clause_mut(where_clause);
+    }
+}
+
+pub fn walk_struct_fields_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut StructFields) {
+    match node {
+        StructFields::Unit => {}
+        StructFields::Tuple(tuple_fields) => {
+            for field in &mut tuple_fields.fields {
+                visitor.visit_tuple_field_mut(field);
+            }
+        }
+        StructFields::Named(named_fields) => {
+            for field in &mut named_fields.fields {
+                visitor.visit_named_field_mut(field);
+            }
+        }
+    }
+}
+
+pub fn walk_named_field_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut NamedField) {
+    for attribute in &mut node.attributes {
+        visitor.visit_attribute_mut(attribute);
+    }
+    if let Some(vis_marker) = &mut node.vis_marker {
+        visitor.visit_vis_marker_mut(vis_marker);
+    }
+    visitor.visit_ty_expr_mut(&mut node.ty);
+}
+
+pub fn walk_tuple_field_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut TupleField) {
+    for attribute in &mut node.attributes {
+        visitor.visit_attribute_mut(attribute);
+    }
+    if let Some(vis_marker) = &mut node.vis_marker {
+        visitor.visit_vis_marker_mut(vis_marker);
+    }
+    visitor.visit_ty_expr_mut(&mut node.ty);
+}
+
+pub fn walk_enum_variant_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut EnumVariant) {
+    for attribute in &mut node.attributes {
+        visitor.visit_attribute_mut(attribute);
+    }
+    if let Some(vis_marker) = &mut node.vis_marker {
+        visitor.visit_vis_marker_mut(vis_marker);
+    }
+    visitor.visit_struct_fields_mut(&mut node.contents);
+    if let Some(discriminant) = &mut node.discriminant {
+        visitor.visit_enum_discriminant_mut(discriminant);
+    }
+}
+
+pub fn walk_function_parameter_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut FunctionParameter,
+) {
+    for attribute in &mut node.attributes {
+        visitor.visit_attribute_mut(attribute);
+    }
+    visitor.visit_ty_expr_mut(&mut node.ty);
+}
+
+pub fn walk_generic_params_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut GenericParams) {
+    for param in &mut node.params {
+        visitor.visit_generic_param_mut(param);
+    }
+}
+
+pub fn walk_generic_param_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut GenericParam) {
+    for attribute in &mut node.attributes {
+        visitor.visit_attribute_mut(attribute);
+    }
+    if let Some(bound) = &mut node.bound {
+        visitor.visit_generic_bound_mut(bound);
+    }
+    if let Some(default) = &mut node.default {
+        visitor.visit_generic_default_mut(default);
+    }
+}
+
+pub fn walk_where_clause_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut WhereClause) {
+    for item in &mut node.items {
+        visitor.visit_where_clause_item_mut(item);
+    }
+}
+
+pub fn walk_where_clause_item_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut WhereClauseItem,
+) {
+    match node {
+        WhereClauseItem::Lifetime(predicate) => visitor.visit_lifetime_predicate_mut(predicate),
+        WhereClauseItem::Ty(predicate) => visitor.visit_ty_predicate_mut(predicate),
+    }
+}
+
+pub fn walk_lifetime_predicate_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut LifetimePredicate,
+) {
+    visitor.visit_lifetime_mut(&mut node.lifetime);
+    for bound in &mut node.bounds {
+        visitor.visit_lifetime_mut(bound);
+    }
+}
+
+pub fn walk_ty_predicate_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut TyPredicate) {
+    if let Some(for_binder) = &mut node.for_binder {
+        visitor.visit_for_binder_mut(for_binder);
+    }
+    visitor.visit_ty_expr_mut(&mut node.left_side);
+    for bound in &mut node.bounds {
+        visitor.visit_ty_bound_mut(bound);
+    }
+}
+
+pub fn walk_ty_bound_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut TyBound) {
+    match node {
+        TyBound::Lifetime(lifetime) => visitor.visit_lifetime_mut(lifetime),
+        TyBound::Trait(trait_bound) => visitor.visit_trait_bound_mut(trait_bound),
+    }
+}
+
+pub fn walk_trait_bound_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut TraitBound) {
+    if let Some(for_binder) = &mut node.for_binder {
+        visitor.visit_for_binder_mut(for_binder);
+    }
+    visitor.visit_ty_expr_mut(&mut node.path);
+}
+
+pub fn walk_for_binder_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut ForBinder) {
+    visitor.visit_generic_params_mut(&mut node.params);
+}